@@ -32,10 +32,14 @@ async function traverse (dir, accumulator = []) {
 }
 // endregion
 
-traverse('src').then(entryPoints => {
+const { dependencies, devDependencies } = require('./package.json')
+
+traverse('src').then(files => {
+  const isBin = file => file.startsWith(path.resolve('src/bin') + path.sep)
+
   // ES-modules unminified
   esbuild.build({
-    entryPoints,
+    entryPoints: files.filter(file => !isBin(file)),
     sourcemap: true,
     minify: false,
     format: 'esm',
@@ -44,6 +48,23 @@ traverse('src').then(entryPoints => {
   }).catch(() => {
     console.error('ES-modules unminified build failed')
   })
+
+  // Binaries are bundled, since node can't resolve the path aliases (`core/...`) the library's modules import each other by.
+  // Packages stay external, and split chunks keep the renderers' dynamic imports lazy, so pixi.js isn't loaded in the terminal
+  esbuild.build({
+    entryPoints: files.filter(isBin),
+    bundle: true,
+    splitting: true,
+    platform: 'node',
+    external: [...Object.keys(dependencies), ...Object.keys(devDependencies)],
+    sourcemap: true,
+    minify: false,
+    format: 'esm',
+    outdir: 'out/bin',
+    watch: process.argv.includes('--watch')
+  }).catch(() => {
+    console.error('Binaries build failed')
+  })
 }).catch(err => {
   console.error('Traversing source files failed', err)
 })
@@ -0,0 +1,25 @@
+#! /usr/bin/env node
+import { attachTerminal } from 'renderer/cli/remote'
+
+// Usage: devolve-ui-attach <socket path | port | host:port>
+const arg = process.argv[2]
+if (arg === undefined) {
+  console.error('usage: devolve-ui-attach <socket path | port | host:port>')
+  process.exit(1)
+}
+
+const hostPort = /^(.+):(\d+)$/.exec(arg)
+const address = hostPort !== null
+  ? { host: hostPort[1], port: parseInt(hostPort[2]) }
+  : /^\d+$/.test(arg)
+    ? parseInt(arg)
+    : arg
+
+async function main (): Promise<void> {
+  await attachTerminal(address)
+}
+
+main().then(() => process.exit(0), error => {
+  console.error('devolve-ui-attach failed', error)
+  process.exit(1)
+})
@@ -0,0 +1,209 @@
+#! /usr/bin/env node
+import {
+  AutomationScript,
+  Breadcrumbs,
+  Color,
+  createRouter,
+  intrinsics,
+  Palette,
+  useInput,
+  useState,
+  useStateMachine,
+  useStopwatch,
+  VNode,
+  WizardSteps
+} from 'core'
+import { VComponent } from 'core/component'
+import { DevolveUI, MockRendererImpl } from 'render-esm'
+
+// Usage: devolve-ui-gallery [--check]
+// Shows each widget and feature on its own screen, chosen from a menu.
+// With --check, runs a script which visits every screen and asserts it rendered, checks that changing a text only redraws its rect,
+// then exits (non-zero on failure)
+
+type Route = 'menu' | 'breadcrumbs' | 'wizard' | 'stopwatch' | 'palettes' | 'state-machine'
+
+interface Screen {
+  route: Exclude<Route, 'menu'>
+  label: string
+  /** Component key, also used to check the screen rendered */
+  key: string
+  component: () => VNode
+}
+
+const SCREENS: Screen[] = [
+  { route: 'breadcrumbs', label: 'Breadcrumbs', key: 'BreadcrumbsScreen', component: BreadcrumbsScreen },
+  { route: 'wizard', label: 'Wizard steps', key: 'WizardScreen', component: WizardScreen },
+  { route: 'stopwatch', label: 'Stopwatch', key: 'StopwatchScreen', component: StopwatchScreen },
+  { route: 'palettes', label: 'Palettes', key: 'PalettesScreen', component: PalettesScreen },
+  { route: 'state-machine', label: 'State machine', key: 'StateMachineScreen', component: StateMachineScreen }
+]
+
+const router = createRouter<Route>()
+
+function Gallery (): VNode {
+  router.useProvide('menu', { backKeys: ['escape'] })
+  return intrinsics.vbox({ x: 1, y: 1, gap: 1 },
+    VComponent('Breadcrumbs', {}, () => {
+      const navigate = router.useNavigate()
+      return Breadcrumbs({
+        items: ['Gallery', ...(navigate.route === 'menu' ? [] : [SCREENS.find(screen => screen.route === navigate.route)!.label])],
+        onClick: () => navigate.replace('menu')
+      })
+    }),
+    VComponent('Outlet', {}, () => router.Outlet({
+      render: route => {
+        if (route === 'menu') {
+          return VComponent('Menu', {}, Menu)
+        }
+        const screen = SCREENS.find(screen => screen.route === route)!
+        return VComponent(screen.key, {}, screen.component)
+      }
+    })),
+    intrinsics.text({ color: 'gray' }, 'up/down and enter to choose, escape to go back, ctrl+c to quit')
+  )
+}
+
+function Menu (): VNode {
+  const navigate = router.useNavigate()
+  const selected = useState(0)
+  useInput(key => {
+    if (key.name === 'up') {
+      selected.v = (selected.v + SCREENS.length - 1) % SCREENS.length
+    } else if (key.name === 'down') {
+      selected.v = (selected.v + 1) % SCREENS.length
+    } else if (key.name === 'return' || key.name === 'enter') {
+      navigate.push(SCREENS[selected.v].route)
+    }
+  })
+  return intrinsics.vbox({}, ...SCREENS.map((screen, index) => intrinsics.text({
+    key: screen.route,
+    color: index === selected.v ? 'white' : 'gray',
+    onClick: () => navigate.push(screen.route)
+  }, `${index === selected.v ? '>' : ' '} ${screen.label}`)))
+}
+
+function BreadcrumbsScreen (): VNode {
+  const depth = useState(3)
+  const items = ['Home', 'Projects', 'devolve-ui', 'src', 'core'].slice(0, depth.v)
+  return intrinsics.vbox({ gap: 1 },
+    Breadcrumbs({ items, onClick: index => { depth.v = index + 1 } }),
+    intrinsics.text({ color: 'gray', onClick: () => { depth.v = Math.min(depth.v + 1, 5) } }, '[deeper]')
+  )
+}
+
+function WizardScreen (): VNode {
+  const current = useState(1)
+  useInput(key => {
+    if (key.name === 'left') {
+      current.v = Math.max(0, current.v - 1)
+    } else if (key.name === 'right') {
+      current.v = Math.min(3, current.v + 1)
+    }
+  })
+  return WizardSteps({ steps: ['Account', 'Profile', 'Confirm'], current: current.v, onClick: index => { current.v = index } })
+}
+
+function StopwatchScreen (): VNode {
+  const stopwatch = useStopwatch()
+  useInput(key => {
+    if (key.name === 'space') {
+      if (stopwatch.isRunning) {
+        stopwatch.pause()
+      } else {
+        stopwatch.resume()
+      }
+    } else if (key.name === 'r') {
+      stopwatch.reset()
+    }
+  })
+  return intrinsics.vbox({},
+    intrinsics.text({ color: 'white' }, `${(stopwatch.elapsedMs / 1000).toFixed(1)}s`),
+    intrinsics.text({ color: 'gray' }, 'space to pause/resume, r to reset')
+  )
+}
+
+function PalettesScreen (): VNode {
+  const palettes: Array<[string, Palette]> = [['Default', Palette.DEFAULT], ['Color-blind safe', Palette.COLOR_BLIND_SAFE], ['High contrast', Palette.HIGH_CONTRAST]]
+  return intrinsics.vbox({ gap: 1 }, ...palettes.map(([name, palette]) => intrinsics.vbox({ key: name },
+    intrinsics.text({ color: palette.foreground }, name),
+    intrinsics.hbox({ gap: 1 }, ...(['accent', 'info', 'success', 'warning', 'error'] as const).map(purpose => intrinsics.text(
+      { key: purpose, color: palette[purpose] },
+      `${purpose} ${Color.contrastRatio(palette[purpose], palette.background).toFixed(1)}`
+    )))
+  )))
+}
+
+function StateMachineScreen (): VNode {
+  const light = useStateMachine<'green' | 'yellow' | 'red', 'next'>({
+    initial: 'red',
+    transition: state => state === 'red' ? 'green' : state === 'green' ? 'yellow' : 'red'
+  })
+  useInput(key => {
+    if (key.name === 'space') {
+      light.send('next')
+    }
+  })
+  return intrinsics.vbox({},
+    intrinsics.text({ color: light.name === 'yellow' ? 'gold' : light.name }, `● ${light.name}`),
+    intrinsics.text({ color: 'gray' }, 'space for the next light')
+  )
+}
+
+/** Visits every screen from the menu and checks that it rendered */
+const CHECK_SCRIPT: AutomationScript = SCREENS.flatMap((screen, index): AutomationScript => [
+  { action: 'assert', selector: `Menu text#${screen.route}` },
+  ...Array.from({ length: index }, (): AutomationScript[number] => ({ action: 'key', key: 'down' })),
+  { action: 'key', key: 'return' },
+  { action: 'waitFor', selector: screen.key, timeoutMs: 1000 },
+  { action: 'key', key: 'escape' },
+  { action: 'waitFor', selector: 'Menu', timeoutMs: 1000 }
+])
+
+/** Checks that changing one text only marks its own rect as changed, so the terminal only rewrites that row */
+function checkDirtyRects (): void {
+  let setLabel = (_label: string): void => {}
+  const renderer = new MockRendererImpl(() => VComponent('DirtyRectsCheck', {}, () =>
+    intrinsics.vbox({},
+      intrinsics.text({ key: 'title' }, 'Title'),
+      VComponent('Label', {}, () => {
+        const label = useState('before')
+        setLabel = text => { label.v = text }
+        return intrinsics.text({ key: 'label' }, label.v)
+      })
+    )
+  ))
+  try {
+    renderer.forceRerender()
+    setLabel('after!')
+    renderer.forceRerender()
+    const dirtyRects = renderer.lastFrame?.dirtyRects ?? null
+    if (dirtyRects?.length !== 1 || dirtyRects[0].top !== 1 || dirtyRects[0].height !== 1 || dirtyRects[0].width > 6) {
+      throw new Error(`changing one text should only mark its rect as changed, but marked ${JSON.stringify(dirtyRects)}`)
+    }
+  } finally {
+    renderer.dispose()
+  }
+}
+
+async function main (): Promise<void> {
+  const ui = new DevolveUI(Gallery, {})
+  if (process.argv.includes('--check')) {
+    checkDirtyRects()
+    ui.show()
+    try {
+      await ui.automate(CHECK_SCRIPT)
+      await ui.shutdownAsync()
+    } catch (error) {
+      ui.close()
+      throw error
+    }
+  } else {
+    await ui.run()
+  }
+}
+
+main().then(() => process.exit(0), error => {
+  console.error(`devolve-ui-gallery ${process.argv.includes('--check') ? 'check ' : ''}failed`, error)
+  process.exit(1)
+})
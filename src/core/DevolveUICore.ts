@@ -1,9 +1,9 @@
 import { Lens } from 'core/lens'
 import { VComponent } from 'core/component'
-import { Renderer, VNode } from 'core/index'
+import { AccessibilityTree, Automation, AutomationScript, FrameStats, InputSource, Keymap, Locale, PLATFORM, QueryMatch, QueryPredicate, Renderer, ShutdownSummary, useKeymap, useProvideDirection, useProvideLocale, VNode, WritingDirection } from 'core/index'
 import type { TerminalRenderOptions } from 'renderer/cli'
 import type { BrowserRenderOptions } from 'renderer/web'
-import { DeepReadonly } from '@raycenity/misc-ts'
+import { DeepReadonly, Key } from '@raycenity/misc-ts'
 
 export type RenderOptions =
   TerminalRenderOptions &
@@ -13,6 +13,10 @@ export abstract class DevolveUICore<Props extends object> {
   protected abstract mkRenderer (root: () => VComponent, opts?: RenderOptions): Renderer
 
   private readonly instance: Renderer
+  private closeListeners: Array<() => void> = []
+  private appKeymap: Keymap = {}
+  private appLocale: Locale | null = null
+  private appDirection: WritingDirection | null = null
   protected readonly props: Props
   /** A proxy which sets the given property */
   readonly p: Lens<Props>
@@ -27,7 +31,7 @@ export abstract class DevolveUICore<Props extends object> {
   constructor (private readonly RootComponent: (props: Props) => VNode, props: Props, opts?: RenderOptions) {
     // Idk why the cast is necessary
     this.props = { ...props }
-    this.instance = this.mkRenderer(() => VComponent('RootComponent', this.props, RootComponent), opts)
+    this.instance = this.mkRenderer(() => VComponent('RootComponent', this.props, this.withAppContext(RootComponent)), opts)
     this.p = this.propsLens(this.props)
   }
 
@@ -49,6 +53,178 @@ export abstract class DevolveUICore<Props extends object> {
 
   close (): void {
     this.instance.dispose()
+    this.didClose()
+  }
+
+  /**
+   * Like {@link close}, but returns a summary of what was left: whether there were unrendered changes and the last frame.
+   * If `flush` is set, renders pending changes first.
+   */
+  shutdown (flush: boolean = false): ShutdownSummary {
+    const summary = this.instance.shutdown(flush)
+    this.didClose()
+    return summary
+  }
+
+  /**
+   * Like {@link shutdownAsync}, but first asks components with {@link useExitBlocker} (e.g. to confirm unsaved changes).
+   * Resolves with null if one of them cancelled, and the UI keeps running
+   */
+  async requestClose (flush: boolean = true): Promise<ShutdownSummary | null> {
+    const summary = await this.instance.requestShutdown(flush)
+    if (summary !== null) {
+      this.didClose()
+    }
+    return summary
+  }
+
+  /** {@link shutdown} (flushing by default), then resolves once all output has been written */
+  async shutdownAsync (flush: boolean = true): Promise<ShutdownSummary> {
+    const summary = await this.instance.shutdownAsync(flush)
+    this.didClose()
+    return summary
+  }
+
+  /** App-wide key bindings, see {@link useKeymap}. Returns this, so it can be chained before {@link run} */
+  keymap (keymap: Keymap): this {
+    this.appKeymap = keymap
+    this.updateProps()
+    return this
+  }
+
+  /** Provides the locale to every component, see {@link useLocale}. Returns this, so it can be chained before {@link run} */
+  locale (locale: Locale): this {
+    this.appLocale = locale
+    this.updateProps()
+    return this
+  }
+
+  /** Provides the writing direction to every component, see {@link useDirection}. Returns this, so it can be chained before {@link run} */
+  direction (direction: WritingDirection): this {
+    this.appDirection = direction
+    this.updateProps()
+    return this
+  }
+
+  /**
+   * Shows the UI and resolves once it's closed, for apps which are just the UI.
+   * Ctrl+C, and in node SIGINT and SIGTERM, call {@link requestClose}, so components with {@link useExitBlocker} can cancel.
+   *
+   * ```ts
+   * const ui = new DevolveUI(App, {}).locale({ tag: 'de' })
+   * await ui.keymap({ q: () => { void ui.requestClose() } }).run()
+   * ```
+   */
+  async run (): Promise<void> {
+    const closed = new Promise<void>(resolve => this.closeListeners.push(resolve))
+    const requestClose = (): void => { void this.requestClose() }
+    const removeInput = this.instance.useInput(key => {
+      if (key.ctrl === true && key.name === 'c') {
+        requestClose()
+      }
+    })
+    const hasSignals = PLATFORM === 'cli' && typeof process !== 'undefined'
+    if (hasSignals) {
+      process.on('SIGINT', requestClose)
+      process.on('SIGTERM', requestClose)
+    }
+
+    this.show()
+    try {
+      await closed
+    } finally {
+      removeInput()
+      if (hasSignals) {
+        process.off('SIGINT', requestClose)
+        process.off('SIGTERM', requestClose)
+      }
+    }
+  }
+
+  private didClose (): void {
+    const listeners = this.closeListeners
+    this.closeListeners = []
+    for (const listener of listeners) {
+      listener()
+    }
+  }
+
+  /**
+   * Swaps in a new version of the root component, keeping components' state, e.g. when the app's code changes in development.
+   * For children to change too, the new root must reference their new versions, e.g. re-import a bundle of the whole app:
+   *
+   * ```ts
+   * fs.watch('out/app.js', async () => {
+   *   const { App } = await import(`./app.js?t=${Date.now()}`)
+   *   ui.hotReload(App)
+   * })
+   * ```
+   *
+   * See {@link VComponent.hotReload} for which hooks keep their state and which effects run
+   */
+  hotReload (RootComponent: (props: Props) => VNode): void {
+    this.instance.hotReload(this.withAppContext(RootComponent))
+  }
+
+  /** Frame timing, overruns and dropped frames, to detect when rendering can't keep up */
+  getFrameStats (): FrameStats {
+    return this.instance.getFrameStats()
+  }
+
+  /** Shows or hides the layout debug overlay: each box's rect, and each view's bounds spec and resulting rect */
+  setLayoutDebug (enabled: boolean): void {
+    this.instance.setLayoutDebug(enabled)
+  }
+
+  /** Finds views in the current tree by selector or predicate, e.g. `ui.query("text:contains('Save')")` */
+  query (selector: string | QueryPredicate): QueryMatch[] {
+    return this.instance.query(selector)
+  }
+
+  /** Roles, labels, values, focus and rects of the views in the current tree, see {@link AccessibilityNode} */
+  accessibilityTree (): AccessibilityTree {
+    return this.instance.accessibilityTree()
+  }
+
+  /**
+   * Merges another input source (e.g. a channel of synthetic events) into the UI's input, alongside the platform's input.
+   * `subscribe` is called with a function to send events, and returns a function to unsubscribe.
+   * Returns a function which removes the source.
+   */
+  addInputSource (source: InputSource, subscribe: (send: (key: Key) => void) => () => void): () => void {
+    return this.instance.addInputSource(source, subscribe)
+  }
+
+  /** The number of times the UI has rerendered, see {@link waitForRerender} */
+  getGeneration (): number {
+    return this.instance.getGeneration()
+  }
+
+  /**
+   * Resolves once the UI rerenders after `afterGeneration` (default: now).
+   * e.g. `const gen = ui.getGeneration(); ui.p.count = 5; await ui.waitForRerender(gen)` waits until the new count is shown
+   */
+  async waitForRerender (afterGeneration?: number): Promise<number> {
+    return await this.instance.waitForRerender(afterGeneration)
+  }
+
+  /** Runs an automation script of input events, waits and assertions against the UI, see {@link Automation.run} */
+  async automate (script: AutomationScript | string): Promise<void> {
+    await Automation.run(this.instance, script)
+  }
+
+  /** Wraps the root component so it provides what was set by {@link keymap}, {@link locale} and {@link direction} */
+  private withAppContext (RootComponent: (props: Props) => VNode): (props: Props) => VNode {
+    return props => {
+      useKeymap(this.appKeymap)
+      if (this.appLocale !== null) {
+        useProvideLocale(this.appLocale)
+      }
+      if (this.appDirection !== null) {
+        useProvideDirection(this.appDirection)
+      }
+      return RootComponent(props)
+    }
   }
 
   protected propsLens<T extends object>(props: T): Lens<T> {
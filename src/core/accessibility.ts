@@ -0,0 +1,102 @@
+import type { AccessibilityRole, Rectangle, VNode, VView } from 'core/view'
+
+/**
+ * A node of the accessibility tree. Serialized as JSON as-is, so this is also the schema for external tooling:
+ *
+ * ```json
+ * {
+ *   "version": 1,
+ *   "root": {
+ *     "viewId": 3,
+ *     "role": "group",
+ *     "label": null,
+ *     "value": null,
+ *     "focused": false,
+ *     "rect": { "left": 0, "top": 0, "width": 80, "height": 24 },
+ *     "children": [{ "viewId": 5, "role": "button", "label": "Save", "value": null, "focused": true, "rect": ..., "children": [] }]
+ *   }
+ * }
+ * ```
+ */
+export interface AccessibilityNode {
+  viewId: number
+  role: Exclude<AccessibilityRole, 'none'>
+  label: string | null
+  value: string | null
+  focused: boolean
+  /** Where the view was last rendered, in columns and rows. null if it hasn't been rendered or is empty */
+  rect: Rectangle | null
+  children: AccessibilityNode[]
+}
+
+export interface AccessibilityTree {
+  /** Incremented on breaking changes to the schema */
+  version: 1
+  root: AccessibilityNode | null
+}
+
+export module Accessibility {
+  export const SCHEMA_VERSION = 1
+
+  /**
+   * Builds the accessibility tree under `root`. `getRect` gets where the view was last rendered.
+   *
+   * Views without semantics (boxes without a role, label or `onClick`, colors, borders, role `'none'`) are skipped and their children hoisted.
+   * Invisible views and their children are skipped
+   */
+  export function tree (root: VNode, getRect: (view: VView) => Rectangle | null): AccessibilityTree {
+    const nodes = build(root, getRect)
+    return {
+      version: SCHEMA_VERSION,
+      root: nodes.length === 1 ? nodes[0] : nodes.length === 0 ? null : { viewId: -1, role: 'group', label: null, value: null, focused: false, rect: null, children: nodes }
+    }
+  }
+
+  /** All nodes in the tree, depth-first, e.g. to find the focused one */
+  export function flatten (node: AccessibilityNode | null): AccessibilityNode[] {
+    return node === null ? [] : [node, ...node.children.flatMap(flatten)]
+  }
+
+  function build (node: VNode, getRect: (view: VView) => Rectangle | null): AccessibilityNode[] {
+    while (node.type === 'component') {
+      if (node.node === null) {
+        return []
+      }
+      node = node.node
+    }
+    if (node.visible === false) {
+      return []
+    }
+
+    const children = node.type === 'box' || node.type === 'fragment'
+      ? node.children.flatMap(child => build(child, getRect))
+      : []
+    const role = node.role ?? defaultRole(node)
+    if (role === null || role === 'none') {
+      return children
+    }
+    return [{
+      viewId: node.id,
+      role,
+      label: node.label ?? null,
+      value: node.value ?? (node.type === 'text' ? node.text : null),
+      focused: node.focused ?? node.focus?.isFocused ?? false,
+      rect: getRect(node),
+      children
+    }]
+  }
+
+  /** null if the view has no semantics of its own */
+  function defaultRole (view: VView): Exclude<AccessibilityRole, 'none'> | null {
+    switch (view.type) {
+      case 'text':
+        return view.link !== undefined ? 'link' : 'text'
+      case 'source':
+        return 'image'
+      case 'box':
+        return view.onClick !== undefined ? 'button' : view.label !== undefined || view.focused !== undefined || view.focus !== undefined ? 'group' : null
+      default:
+        return null
+    }
+  }
+}
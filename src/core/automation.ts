@@ -0,0 +1,194 @@
+import type { Renderer } from 'core/renderer'
+import { Query, QueryMatch } from 'core/query'
+import { Rectangle } from 'core/view'
+import type { Key } from '@raycenity/misc-ts'
+
+/**
+ * One step of an {@link AutomationScript}. Selectors are the same as {@link Renderer.query}
+ *
+ * - `key`: sends a key press, e.g. `{ action: 'key', key: 'enter' }` or `{ action: 'key', key: 'c', ctrl: true }`
+ * - `type`: sends each character of `text` as a key press
+ * - `click`: presses and releases the left mouse button at the center of the first view matching `selector`, or at `x` and `y`
+ * - `wait`: waits `ms` milliseconds
+ * - `rerender`: waits until the renderer rerenders
+ * - `waitFor`: waits until a view matches `selector` (checking after each rerender), fails after `timeoutMs` (default 5000)
+ * - `assert`: fails unless `count` views (default at least 1) match `selector`, and if `at` is provided, one contains that position
+ */
+export type AutomationStep =
+  { action: 'key', key: string, ctrl?: boolean, meta?: boolean, shift?: boolean } |
+  { action: 'type', text: string } |
+  { action: 'click', selector: string } |
+  { action: 'click', x: number, y: number } |
+  { action: 'wait', ms: number } |
+  { action: 'rerender' } |
+  { action: 'waitFor', selector: string, timeoutMs?: number } |
+  { action: 'assert', selector: string, count?: number, at?: { x: number, y: number } }
+
+/** A sequence of input events, waits and assertions, e.g. parsed from JSON */
+export type AutomationScript = AutomationStep[]
+
+export class AutomationError extends Error {
+  constructor (readonly stepIndex: number, readonly step: AutomationStep, message: string) {
+    super(`automation step ${stepIndex} (${step.action}) failed: ${message}`)
+  }
+}
+
+const DEFAULT_WAIT_FOR_TIMEOUT_MS = 5000
+
+export module Automation {
+  /** Parses a script from JSON (an array of steps). Throws if a step has an unknown action or is missing a field it needs */
+  export function parse (json: string): AutomationScript {
+    const script: unknown = JSON.parse(json)
+    if (!Array.isArray(script)) {
+      throw new Error('automation script must be an array of steps')
+    }
+    script.forEach(validateStep)
+    return script
+  }
+
+  /** So a bad script fails when it's parsed, instead of partway through running */
+  function validateStep (step: unknown, index: number): asserts step is AutomationStep {
+    const fail = (message: string): never => {
+      throw new Error(`automation step ${index} is invalid: ${message}`)
+    }
+    if (typeof step !== 'object' || step === null) {
+      return fail('not an object')
+    }
+    const fields = step as Record<string, unknown>
+    const expect = (field: string, type: 'string' | 'number' | 'boolean', isOptional: boolean = false): void => {
+      if (fields[field] === undefined ? !isOptional : typeof fields[field] !== type) {
+        fail(`${field} must be a ${type}`)
+      }
+    }
+    switch (fields.action) {
+      case 'key':
+        expect('key', 'string')
+        expect('ctrl', 'boolean', true)
+        expect('meta', 'boolean', true)
+        expect('shift', 'boolean', true)
+        break
+      case 'type':
+        expect('text', 'string')
+        break
+      case 'click':
+        if ('selector' in fields) {
+          expect('selector', 'string')
+        } else {
+          expect('x', 'number')
+          expect('y', 'number')
+        }
+        break
+      case 'wait':
+        expect('ms', 'number')
+        break
+      case 'rerender':
+        break
+      case 'waitFor':
+        expect('selector', 'string')
+        expect('timeoutMs', 'number', true)
+        break
+      case 'assert': {
+        expect('selector', 'string')
+        expect('count', 'number', true)
+        const at = fields.at as Record<string, unknown> | null | undefined
+        if (at !== undefined && (typeof at !== 'object' || at === null || typeof at.x !== 'number' || typeof at.y !== 'number')) {
+          fail('at must be an object with numbers x and y')
+        }
+        break
+      }
+      default:
+        fail(`unknown action ${JSON.stringify(fields.action)}`)
+    }
+  }
+
+  /**
+   * Runs `script` against a running renderer, one step at a time.
+   * Rejects with an {@link AutomationError} on the first failed step
+   */
+  export async function run (renderer: Renderer, script: AutomationScript | string): Promise<void> {
+    if (typeof script === 'string') {
+      script = parse(script)
+    }
+    for (let i = 0; i < script.length; i++) {
+      const step = script[i]
+      const error = await runStep(renderer, step)
+      if (error !== null) {
+        throw new AutomationError(i, step, error)
+      }
+    }
+  }
+
+  /** Returns an error message if the step failed */
+  async function runStep (renderer: Renderer, step: AutomationStep): Promise<string | null> {
+    switch (step.action) {
+      case 'key':
+        renderer.sendInput(mkKey(step.key, step))
+        return null
+      case 'type':
+        for (const char of step.text) {
+          renderer.sendInput(mkKey(char === ' ' ? 'space' : char, { shift: char !== char.toLowerCase() }))
+        }
+        return null
+      case 'click': {
+        let position: { x: number, y: number }
+        if ('selector' in step) {
+          const match = renderer.query(step.selector).find(match => match.rect !== null)
+          if (match === undefined) {
+            return `no rendered view matches ${step.selector}`
+          }
+          position = Query.center(match)!
+        } else {
+          position = { x: step.x, y: step.y }
+        }
+        renderer.sendMouse({ type: 'press', button: 'left', ...position })
+        renderer.sendMouse({ type: 'release', button: 'left', ...position })
+        return null
+      }
+      case 'wait':
+        await new Promise(resolve => setTimeout(resolve, step.ms))
+        return null
+      case 'rerender':
+        await renderer.waitForRerender()
+        return null
+      case 'waitFor': {
+        const timeoutMs = step.timeoutMs ?? DEFAULT_WAIT_FOR_TIMEOUT_MS
+        const deadline = Date.now() + timeoutMs
+        while (renderer.query(step.selector).length === 0) {
+          const remainingMs = deadline - Date.now()
+          if (remainingMs <= 0) {
+            return `no view matched ${step.selector} within ${timeoutMs}ms`
+          }
+          let timeout: ReturnType<typeof setTimeout> | undefined
+          await Promise.race([
+            renderer.waitForRerender(),
+            new Promise(resolve => { timeout = setTimeout(resolve, remainingMs) })
+          ])
+          clearTimeout(timeout)
+        }
+        return null
+      }
+      case 'assert': {
+        const matches = renderer.query(step.selector)
+        if (step.count !== undefined ? matches.length !== step.count : matches.length === 0) {
+          return `expected ${step.count ?? 'at least 1'} views to match ${step.selector}, got ${matches.length}`
+        }
+        if (step.at !== undefined && !matches.some(match => containsPosition(match, step.at!))) {
+          return `no view matching ${step.selector} is at (${step.at.x}, ${step.at.y})`
+        }
+        return null
+      }
+      default:
+        return `unknown action ${JSON.stringify((step as { action: unknown }).action)}`
+    }
+  }
+
+  function containsPosition (match: QueryMatch, position: { x: number, y: number }): boolean {
+    return match.rect !== null && Rectangle.contains(match.rect, position)
+  }
+
+  /** Creates a key like the ones from the terminal */
+  function mkKey (name: string, { ctrl, meta, shift }: { ctrl?: boolean, meta?: boolean, shift?: boolean }): Key {
+    // eslint-disable-next-line @typescript-eslint/consistent-type-assertions
+    return { name: name.length === 1 ? name.toLowerCase() : name, sequence: name === 'space' ? ' ' : name, ctrl: ctrl ?? false, meta: meta ?? false, shift: shift ?? false } as Key
+  }
+}
@@ -1,9 +1,11 @@
 import { PLATFORM } from 'core/platform'
-import { PixiComponent, VNode } from 'core/view'
+import { PixiComponent, VFragment, VJSX, VNode } from 'core/view'
 import { RendererImpl } from 'renderer/common'
 import { Lens } from 'core/lens'
 import { assert, deepAssign, Strings } from '@raycenity/misc-ts'
 import { Context } from 'core/hooks/intrinsic/context'
+import type { RenderLogEntry } from 'core/render-log'
+import { Profile } from 'core/profile'
 
 type PendingUpdateDetails = string
 
@@ -25,7 +27,11 @@ export interface VComponent<Props = any> {
   readonly permanentDestructors: Array<() => void>
 
   readonly children: Map<string, VComponent>
+  /** null for the root */
+  readonly parent: VComponent | null
   readonly renderer: RendererImpl<any, any>
+  /** Added to errors thrown while updating this component or its descendants, see {@link useErrorContext} */
+  readonly errorContexts: Array<() => string>
 
   isBeingUpdated: boolean
   isFresh: boolean
@@ -59,15 +65,61 @@ export function * iterVComponentAncestorsTopDown (): Generator<VComponent> {
   }
 }
 
+/** Set during {@link VComponent.hotReload}, so hooks added by the new code create their state */
+let IS_HOT_RELOADING = false
+
+/** Errors which already have their component path, so it isn't added again by each ancestor */
+const ERRORS_WITH_COMPONENT_CONTEXT: WeakSet<Error> = new WeakSet()
+
 function withVComponent<T> (component: VComponent, body: () => T): T {
   VCOMPONENT_STACK.push(component)
   try {
     return body()
+  } catch (error) {
+    throw addComponentContext(error, component)
   } finally {
     VCOMPONENT_STACK.pop()
   }
 }
 
+/**
+ * Keys of the component and its ancestors from the root, e.g. `App/Settings/Checkbox`.
+ * Default is the current component, and an empty string if there is none
+ */
+export function getVComponentPath (component: VComponent | null = VCOMPONENT_STACK[VCOMPONENT_STACK.length - 1] ?? null): string {
+  const keys: string[] = []
+  for (let ancestor = component; ancestor !== null; ancestor = ancestor.parent) {
+    keys.push(ancestor.key)
+  }
+  return keys.reverse().join('/')
+}
+
+/** Adds the path of the component the error was thrown in, and the contexts from {@link useErrorContext}, to the error's message */
+function addComponentContext (error: unknown, component: VComponent): unknown {
+  if (!(error instanceof Error) || ERRORS_WITH_COMPONENT_CONTEXT.has(error)) {
+    return error
+  }
+  ERRORS_WITH_COMPONENT_CONTEXT.add(error)
+
+  const contexts: string[] = []
+  for (let ancestor: VComponent | null = component; ancestor !== null; ancestor = ancestor.parent) {
+    for (const context of ancestor.errorContexts) {
+      try {
+        contexts.push(context())
+      } catch (contextError) {
+        contexts.push(`<error getting context: ${(contextError as Error).message}>`)
+      }
+    }
+  }
+  const oldMessage = error.message
+  error.message += `\n  in component ${getVComponentPath(component)}${contexts.map(context => `\n  with ${context}`).join('')}`
+  // The stack was computed with the old message
+  if (error.stack?.includes(oldMessage) === true) {
+    error.stack = error.stack.replace(oldMessage, error.message)
+  }
+  return error
+}
+
 function withRenderer<T> (vrenderer: RendererImpl<any, any>, body: () => T): T {
   RENDERER_STACK.push(vrenderer)
   try {
@@ -91,15 +143,15 @@ export function VComponent<Props> (key: string, props: Props, construct: (props:
     const parent = getVComponent()
     // parent is being created = if there are any existing children, they're not being reused, they're a conflict
     if (!VComponent.isBeingCreated(parent)) {
-      for (const [key, component] of parent.children) {
-        // If the componennt was already reused this update, it's a conflict. We fallthrough to VComponent.create which throws the error
-        if (!component.isFresh) {
-          component.props = props
-          component.construct = construct
-          component.isFresh = true
-          VComponent.update(component, `child:${key}`)
-          return component
-        }
+      const component = parent.children.get(key)
+      // If the component was already reused this update, it's a conflict. We fallthrough to VComponent.create which throws the error
+      if (component !== undefined && !component.isFresh) {
+        const propsDiff = VComponent.BuildTree.diffValues(component.props, props)
+        component.props = props
+        component.construct = construct
+        component.isFresh = true
+        VComponent.update(component, `child:${key}${propsDiff}`)
+        return component
       }
     }
   }
@@ -109,6 +161,8 @@ export function VComponent<Props> (key: string, props: Props, construct: (props:
 
 export module VComponent {
   export function create<Props> (key: string, props: Props, construct: (props: Props) => VNode): VComponent {
+    checkDepth(key)
+
     // Create JS object
     const component: VComponent<Props> = {
       type: 'component',
@@ -127,7 +181,9 @@ export module VComponent {
       permanentDestructors: [],
 
       children: new Map(),
+      parent: VCOMPONENT_STACK[VCOMPONENT_STACK.length - 1] ?? null,
       renderer: getRenderer(),
+      errorContexts: [],
 
       isBeingUpdated: false,
       isFresh: true,
@@ -168,7 +224,7 @@ export module VComponent {
       details += '!'
       withRenderer(component.renderer, () => doUpdate(component, details, () => {
         // Actually do construct and set component.node
-        const node = component.construct(component.props)
+        const node = constructNode(component)
         component.node = node
         if (typeof node !== 'object' || Array.isArray(node) || !('type' in node)) {
           throw new Error('JSX components can only return nodes (views or other components). Call this function normally, not with JSX')
@@ -194,12 +250,13 @@ export module VComponent {
       runUpdateDestructors(component)
       component.nextStateIndex = 0
       component.providedContexts.clear()
+      component.errorContexts.length = 0
 
       // Do construct
       // We also need to use VComponent's renderer because the current renderer might be different
       withRenderer(component.renderer, () => doUpdate(component, details, () => {
         const oldNode = component.node!
-        const node = component.construct(component.props)
+        const node = constructNode(component)
         component.node = node
 
         // Update pixi if pixi component and on web
@@ -220,21 +277,46 @@ export module VComponent {
     assert(!component.isDead, 'sanity check: tried to destroy already dead component')
     assert(component.node !== null, 'sanity check: tried to destroy uninitialized component')
 
-    runPermanentDestructors(component)
+    // Children are destroyed even if a destructor throws, so their listeners are still removed
+    callAll([
+      () => runPermanentDestructors(component),
+      () => {
+        const node = component.node!
+        if (node.type === 'pixi' && node.pixi !== 'terminal') {
+          const pixiComponent: PixiComponent<any> = component.construct as PixiComponent<any>
+          pixiComponent.lifecycle.destroy?.(node.pixi)
+          pixiComponent.pixis.splice(pixiComponent.pixis.indexOf(node.pixi), 1)
+        }
+      },
+      () => {
+        const node = component.node!
+        component.isDead = true
+        component.node = null
+        invalidate(component, node)
+      },
+      ...[...component.children.values()].map(child => () => destroy(child))
+    ])
+  }
 
-    const node = component.node
-    if (node.type === 'pixi' && node.pixi !== 'terminal') {
-      const pixiComponent: PixiComponent<any> = component.construct as PixiComponent<any>
-      pixiComponent.lifecycle.destroy?.(node.pixi)
-      pixiComponent.pixis.splice(pixiComponent.pixis.indexOf(node.pixi), 1)
+  /** Calls every function, even if some throw, then rethrows the first error */
+  function callAll (fns: Iterable<() => void>): void {
+    let error: { value: unknown } | null = null
+    for (const fn of fns) {
+      try {
+        fn()
+      } catch (value) {
+        error = error ?? { value }
+      }
     }
+    if (error !== null) {
+      throw error.value
+    }
+  }
 
-    component.isDead = true
-    component.node = null
-    invalidate(component, node)
-
-    for (const child of Object.values(component.children)) {
-      destroy(child)
+  /** Pops and calls destructors until there are none left (destructors might add new destructors), even if some throw */
+  function * popAll (destructors: Array<() => void>): Generator<() => void> {
+    while (destructors.length > 0) {
+      yield destructors.pop()!
     }
   }
 
@@ -265,6 +347,36 @@ export module VComponent {
     }
   }
 
+  /** Throws if creating a component with `key` under the current one would exceed `maxComponentDepth`, e.g. if a component renders itself unconditionally */
+  function checkDepth (key: string): void {
+    const { maxComponentDepth } = GLOBAL_COMPONENT_OPTS
+    const keys = [key]
+    for (let ancestor: VComponent | null = VCOMPONENT_STACK[VCOMPONENT_STACK.length - 1] ?? null; ancestor !== null; ancestor = ancestor.parent) {
+      keys.push(ancestor.key)
+    }
+    if (keys.length <= maxComponentDepth) {
+      return
+    }
+    keys.reverse()
+
+    // Find the shortest segment which repeats at the end of the path
+    let cycle: string[] | null = null
+    for (let length = 1; length * 2 <= keys.length && cycle === null; length++) {
+      const last = keys.slice(-length)
+      const beforeLast = keys.slice(-2 * length, -length)
+      if (last.every((key, i) => key === beforeLast[i])) {
+        cycle = last
+      }
+    }
+    const error = new Error(
+      `component depth exceeded maxComponentDepth (${maxComponentDepth}) at ${keys.slice(0, 3).join('/')}/.../${keys.slice(-3).join('/')}. ` +
+      (cycle !== null ? `The path repeats ${cycle.join('/')}, so a component probably renders itself unconditionally` : 'If the tree is really this deep, raise maxComponentDepth in setGlobalComponentOpts')
+    )
+    // The full path is too long to be useful
+    ERRORS_WITH_COMPONENT_CONTEXT.add(error)
+    throw error
+  }
+
   function clearFreshAndRemoveStaleChildren (component: VComponent): void {
     // Need to copy map because we're going to remove some entries
     for (const [childKey, child] of new Map(component.children)) {
@@ -283,39 +395,56 @@ export module VComponent {
     // Of course, effects can cause more pending updates
     while (component.effects.length > 0 && !component.hasPendingUpdates) {
       const effect = component.effects.pop()!
-      effect()
+      profile('effects', effect)
     }
     // Child effects are taken care of
   }
 
+  /** Calls the component's construct function. If it returns multiple nodes (e.g. `<>...</>`), they're wrapped in a fragment */
+  function constructNode (component: VComponent): VNode {
+    const node: VNode | VJSX[] = profile('render', () => component.construct(component.props))
+    return Array.isArray(node) ? VFragment(VJSX.collapse(node), {}) : node
+  }
+
+  /** If profiling, records how long `body` takes under the current component's path */
+  function profile<T> (section: 'render' | 'effects', body: () => T): T {
+    if (!GLOBAL_COMPONENT_OPTS.profile) {
+      return body()
+    }
+
+    const stack = [...iterVComponentAncestorsTopDown()].map(component => component.key).reverse()
+    stack.push(`[${section}]`)
+    return Profile.measure(stack, body)
+  }
+
   function runUpdateDestructors (component: VComponent): void {
-    // Destructors might add new destructors
-    while (component.updateDestructors.length > 0) {
-      const destructor = component.updateDestructors.pop()!
-      destructor()
+    try {
+      callAll(popAll(component.updateDestructors))
+    } finally {
+      component.updateDestructors.push(...component.nextUpdateDestructors)
+      component.nextUpdateDestructors = []
     }
-    component.updateDestructors.push(...component.nextUpdateDestructors)
-    component.nextUpdateDestructors = []
     // Child update (and permanent if necessary) destructors are taken care of
   }
 
   function runPermanentDestructors (component: VComponent): void {
-    // Destructors might add new destructors
-    while (component.permanentDestructors.length > 0) {
-      const destructor = component.permanentDestructors.pop()!
-      destructor()
-    }
+    callAll(popAll(component.permanentDestructors))
     // Child permanent destructors are taken care of
   }
 
   /** Makes the given component update when the given state changes. hookId is used for the stack trace on update loop */
   export function trackState<T> (component: VComponent, state: Lens<T>, hookId: string): void {
     assert(!component.stateTrackers.has(state), `state ${hookId} is already tracked`)
+    // State is mutated before observers are called, so we have to remember the old value ourselves
+    let oldValue = BuildTree.serializeValue(state.v)
     const stateTracker = (newValue: T, debugPath: string): void => {
       const stackTrace = isDebugMode()
         ? (new Error().stack?.replace('\n', '  \n') ?? 'could not get stack, new Error().stack is undefined')
         : 'omitted in production'
-      update(component, `${hookId}${debugPath}\n${stackTrace}`)
+      const newSerialized = BuildTree.serializeValue(newValue)
+      const diff = newSerialized === null ? '' : `: ${oldValue ?? '?'} -> ${newSerialized}`
+      oldValue = newSerialized
+      update(component, `${hookId}${debugPath}${diff}\n${stackTrace}`)
     }
     component.stateTrackers.set(state, stateTracker)
     Lens.onSet(state, stateTracker)
@@ -335,7 +464,7 @@ export module VComponent {
   export function setProvidedContext (component: VComponent, context: Context, value: any): void {
     assert(!component.providedContexts.has(context), 'setProvidedContext called multiple times with the same provided context in the same update')
     component.providedContexts.set(context, value)
-    for (const child of Object.values(component.children)) {
+    for (const child of component.children.values()) {
       setConsumedContexts(child, context, value)
     }
   }
@@ -348,10 +477,69 @@ export module VComponent {
     return component.node === null
   }
 
-  module BuildTree {
+  /** Whether the hook at state `index` should create its state: the component is being created, or the hook was added by a hot reload */
+  export function isCreatingState (component: VComponent, index: number): boolean {
+    return isBeingCreated(component) || (IS_HOT_RELOADING && index === component.state.length)
+  }
+
+  /**
+   * Replaces the component's construct function (e.g. with a newly-loaded version of the module) and updates it, keeping its state.
+   * Children get their new functions from the new construct, and keep their state too if they are in the same positions.
+   *
+   * Hooks added at the end of a component get their initial state, and added `onChange` and `onDefine` effects run like they do on create.
+   * Added `on-create` effects don't run, because they have no state to tell they were added, so use `{ onChange: [] }` for those.
+   * If a component's hooks were removed or reordered, its state no longer lines up: change its key so it's recreated
+   */
+  export function hotReload<Props> (component: VComponent<Props>, construct: (props: Props) => VNode): void {
+    component.construct = construct
+    IS_HOT_RELOADING = true
+    try {
+      update(component, 'hot-reload')
+    } finally {
+      IS_HOT_RELOADING = false
+    }
+  }
+
+  export module BuildTree {
     let LOCAL_DEPTH: number = 0
     let LOCAL_LOGS: string[] | null = null
 
+    /** If logging values, serializes `value` as JSON truncated to `maxValueLength`, otherwise returns null */
+    export function serializeValue (value: any): string | null {
+      const { enable, logValues, maxValueLength } = GLOBAL_COMPONENT_OPTS.logBuildTree
+      if (!enable || !logValues) {
+        return null
+      }
+
+      let serialized: string
+      try {
+        serialized = JSON.stringify(value, (_, value) =>
+          typeof value === 'function'
+            ? '<function>'
+            : typeof value === 'bigint'
+              ? `${value.toString()}n`
+              : value
+        ) ?? 'undefined'
+      } catch (error) {
+        // e.g. cyclic
+        serialized = `<${(error as Error).message}>`
+      }
+      return serialized.length > maxValueLength ? `${serialized.slice(0, maxValueLength - 1)}…` : serialized
+    }
+
+    /** If logging values, describes the change from `oldValue` to `newValue`, otherwise returns an empty string */
+    export function diffValues (oldValue: any, newValue: any): string {
+      const oldSerialized = serializeValue(oldValue)
+      const newSerialized = serializeValue(newValue)
+      if (oldSerialized === null || newSerialized === null) {
+        return ''
+      } else if (oldSerialized === newSerialized) {
+        return ': unchanged'
+      } else {
+        return `: ${oldSerialized} -> ${newSerialized}`
+      }
+    }
+
     export function log (details: PendingUpdateDetails, action: () => void): void {
       const { enable, width } = GLOBAL_COMPONENT_OPTS.logBuildTree
       if (!enable) {
@@ -394,22 +582,49 @@ export module VComponent {
 
 export interface GlobalComponentOpts {
   maxRecursiveUpdatesBeforeLoopDetected: number
+  /** Creating a component nested deeper than this throws, instead of recursing until the stack overflows */
+  maxComponentDepth: number
   isDebugMode: boolean
   logBuildTree: {
     enable: boolean
     width: number
+    /** Log the old and new values of changed state and props (serialized as JSON) */
+    logValues: boolean
+    /** Serialized values are truncated to this length */
+    maxValueLength: number
   }
   logRender: boolean
+  /** Receives a {@link RenderLogEntry} for every view rendered, e.g. `RenderLog.jsonl(write)` */
+  renderLogSink: ((entry: RenderLogEntry) => void) | null
+  /** Record the time spent in each component's render function and effects, see {@link Profile} */
+  profile: boolean
+  /** Where {@link Diagnostics.warn} sends warnings */
+  warningSink: (message: string) => void
+  /** Only send each distinct warning once */
+  dedupeWarnings: boolean
+  /**
+   * In debug mode, warn when text has a lower contrast ratio (see {@link Color.contrastRatio}) than this
+   * against a `color` view rendered below it. null to disable
+   */
+  minContrastRatio: number | null
 }
 
 export const DEFAULT_GLOBAL_COMPONENT_OPTS: GlobalComponentOpts = {
   maxRecursiveUpdatesBeforeLoopDetected: 100,
+  maxComponentDepth: 256,
   isDebugMode: true,
   logBuildTree: {
     enable: false,
-    width: 128
+    width: 128,
+    logValues: false,
+    maxValueLength: 64
   },
-  logRender: false
+  logRender: false,
+  renderLogSink: null,
+  profile: false,
+  warningSink: message => console.warn(message),
+  dedupeWarnings: true,
+  minContrastRatio: 4.5
 }
 
 const GLOBAL_COMPONENT_OPTS: GlobalComponentOpts = { ...DEFAULT_GLOBAL_COMPONENT_OPTS }
@@ -425,3 +640,15 @@ export function isDebugMode (): boolean {
 export function doLogRender (): boolean {
   return GLOBAL_COMPONENT_OPTS.logRender
 }
+
+export function getWarningSink (): { sink: (message: string) => void, dedupe: boolean } {
+  return { sink: GLOBAL_COMPONENT_OPTS.warningSink, dedupe: GLOBAL_COMPONENT_OPTS.dedupeWarnings }
+}
+
+export function getMinContrastRatio (): number | null {
+  return GLOBAL_COMPONENT_OPTS.isDebugMode ? GLOBAL_COMPONENT_OPTS.minContrastRatio : null
+}
+
+export function getRenderLogSink (): ((entry: RenderLogEntry) => void) | null {
+  return GLOBAL_COMPONENT_OPTS.renderLogSink
+}
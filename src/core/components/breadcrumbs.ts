@@ -0,0 +1,58 @@
+import { intrinsics, Style, VNode } from 'core/view'
+import { Router } from 'core/hooks/router'
+
+export interface BreadcrumbsProps {
+  /** Labels from first to current */
+  items: string[]
+  /** Default is ' > ' */
+  separator?: string
+  /** Called with the index of the clicked item (not called for the current item) */
+  onClick?: (index: number) => void
+  /** Merged over the default styles, e.g. from a theme */
+  styles?: BreadcrumbsStyles
+}
+
+export interface BreadcrumbsStyles {
+  item?: Style
+  current?: Style
+  separator?: Style
+}
+
+const DEFAULT_BREADCRUMBS_STYLES: Required<BreadcrumbsStyles> = {
+  item: { color: 'gray' },
+  current: { color: 'white' },
+  separator: { color: 'gray' }
+}
+
+/** A horizontal trail of labels, with the last (current) one highlighted */
+export function Breadcrumbs ({ items, separator, onClick, styles }: BreadcrumbsProps): VNode {
+  const children: VNode[] = []
+  items.forEach((item, index) => {
+    const isCurrent = index === items.length - 1
+    if (index > 0) {
+      children.push(intrinsics.text({ style: Style.merge(DEFAULT_BREADCRUMBS_STYLES.separator, styles?.separator) }, separator ?? ' > '))
+    }
+    children.push(intrinsics.text({
+      style: isCurrent
+        ? Style.merge(DEFAULT_BREADCRUMBS_STYLES.current, styles?.current)
+        : Style.merge(DEFAULT_BREADCRUMBS_STYLES.item, styles?.item),
+      onClick: isCurrent || onClick === undefined ? undefined : () => onClick(index)
+    }, item))
+  })
+  return intrinsics.hbox({}, ...children)
+}
+
+/**
+ * {@link Breadcrumbs} for the routes of `router` up to the current one, labeled by `label`.
+ * Clicking a route goes back to it
+ */
+export function RouterBreadcrumbs<Route> ({ router, label, separator, styles }: { router: Router<Route>, label: (route: Route) => string, separator?: string, styles?: BreadcrumbsStyles }): VNode {
+  const navigate = router.useNavigate()
+  const { entries, index } = navigate.history
+  return Breadcrumbs({
+    items: entries.slice(0, index + 1).map(label),
+    separator,
+    onClick: navigate.goTo,
+    styles
+  })
+}
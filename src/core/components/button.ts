@@ -0,0 +1,62 @@
+import { BorderStyle, intrinsics, Style, VNode } from 'core/view'
+import { useFocus } from 'core/hooks/focus'
+import { useKeymap } from 'core/hooks/keymap'
+import { useMountedCallback } from 'core/hooks/extra'
+import { Graphemes } from 'core/graphemes'
+
+export interface ButtonProps {
+  label: string
+  onPress: () => void
+  /** Disabled buttons are dimmed, can't be pressed, and are skipped by Tab. Default is true */
+  enabled?: boolean
+  /** Focus when created. Default is false */
+  autoFocus?: boolean
+  /** Merged over the default styles, e.g. from a theme. The border uses the color */
+  styles?: ButtonStyles
+}
+
+export interface ButtonStyles {
+  normal?: Style
+  /** Merged over `normal` while focused */
+  focused?: Style
+}
+
+const DEFAULT_BUTTON_STYLES: Required<ButtonStyles> = {
+  normal: { color: 'white' },
+  focused: { color: 'cyan', bold: true }
+}
+
+/** How much disabled buttons are dimmed, see {@link CommonAttrs.dim} */
+const DISABLED_DIM = 0.5
+
+/**
+ * `label` in a border, which is focusable (see {@link useFocus}) and calls `onPress` when clicked,
+ * or when enter or space is pressed while focused. The border is doubled while focused
+ */
+export function Button ({ label, onPress, enabled, autoFocus, styles }: ButtonProps): VNode {
+  const isEnabled = enabled ?? true
+  const focus = useFocus({ enabled: isEnabled, autoFocus })
+  // Clicks and keys may arrive after the button is gone
+  const press = useMountedCallback(onPress)
+  useKeymap(focus.isFocused
+    ? {
+        return: { description: `Press ${label}`, action: press },
+        space: { description: `Press ${label}`, action: press }
+      }
+    : {})
+
+  const style = focus.isFocused
+    ? Style.merge(DEFAULT_BUTTON_STYLES.normal, styles?.normal, DEFAULT_BUTTON_STYLES.focused, styles?.focused)
+    : Style.merge(DEFAULT_BUTTON_STYLES.normal, styles?.normal)
+  const borderStyle: BorderStyle = focus.isFocused ? 'double' : 'single'
+  return intrinsics.zbox({
+    role: 'button',
+    label,
+    focus,
+    dim: isEnabled ? undefined : DISABLED_DIM,
+    onClick: isEnabled ? press : undefined
+  },
+  intrinsics.text({ key: 'label', x: 2, y: 1, style }, label),
+  intrinsics.border({ key: 'border', width: Graphemes.lineWidth(label) + 4, height: 3, style: borderStyle, color: style.color ?? undefined })
+  )
+}
@@ -0,0 +1,208 @@
+import { intrinsics, Style, VNode } from 'core/view'
+import { getRenderer } from 'core/component'
+import { useEffect, useState } from 'core/hooks/intrinsic'
+import { useKeymap } from 'core/hooks/keymap'
+import { useMountedCallback } from 'core/hooks/extra'
+import { Graphemes } from 'core/graphemes'
+
+export interface ChoiceOption<T> {
+  value: T
+  label: string
+}
+
+export interface ChoiceStyles {
+  item?: Style
+  /** Merged over `item` when the control (or, in a list, the highlighted option) has focus */
+  focused?: Style
+  /** The marker of checked and selected items, e.g. `[x]` */
+  selected?: Style
+}
+
+const DEFAULT_CHOICE_STYLES: Required<ChoiceStyles> = {
+  item: { color: 'white' },
+  focused: { color: 'cyan', bold: true },
+  selected: { color: 'green' }
+}
+
+/** Above the select's siblings, so the open list isn't covered */
+const SELECT_OPEN_Z_INDEX = 1000
+
+function itemStyle (isFocused: boolean, styles: ChoiceStyles | undefined): Style {
+  return isFocused
+    ? Style.merge(DEFAULT_CHOICE_STYLES.item, styles?.item, DEFAULT_CHOICE_STYLES.focused, styles?.focused)
+    : Style.merge(DEFAULT_CHOICE_STYLES.item, styles?.item)
+}
+
+export interface CheckboxProps {
+  label: string
+  checked: boolean
+  onChange: (checked: boolean) => void
+  /** Whether space and enter toggle the checkbox. The parent decides which control has focus, e.g. with {@link useFocus} */
+  focused?: boolean
+  /** Merged over the default styles, e.g. from a theme */
+  styles?: ChoiceStyles
+}
+
+/** `[x] label` or `[ ] label`. Clicking toggles it, as do space and enter while focused */
+export function Checkbox ({ label, checked, onChange, focused, styles }: CheckboxProps): VNode {
+  // Clicks and keys may arrive after the checkbox is gone
+  const change = useMountedCallback(onChange)
+  const toggle = (): void => change(!checked)
+  useKeymap(focused === true
+    ? {
+        space: { description: 'Toggle', action: toggle },
+        return: { description: 'Toggle', action: toggle }
+      }
+    : {})
+
+  return intrinsics.hbox({ role: 'checkbox', label, value: checked ? 'checked' : 'unchecked', focused: focused === true, onClick: toggle },
+    intrinsics.text({ style: Style.merge(itemStyle(focused === true, styles), checked ? Style.merge(DEFAULT_CHOICE_STYLES.selected, styles?.selected) : undefined) }, checked ? '[x]' : '[ ]'),
+    intrinsics.text({ style: itemStyle(focused === true, styles) }, ` ${label}`)
+  )
+}
+
+export interface RadioGroupProps<T> {
+  options: Array<ChoiceOption<T>>
+  /** null if no option is selected */
+  value: T | null
+  onChange: (value: T) => void
+  /** Whether the arrow keys change the selection. The parent decides which control has focus, e.g. with {@link useFocus} */
+  focused?: boolean
+  /** Default is vertical */
+  direction?: 'horizontal' | 'vertical'
+  /** Merged over the default styles, e.g. from a theme */
+  styles?: ChoiceStyles
+}
+
+/** `(•) label` for the selected option and `( ) label` for the others. Clicking an option selects it, as do the arrow keys while focused */
+export function RadioGroup<T> ({ options, value, onChange, focused, direction, styles }: RadioGroupProps<T>): VNode {
+  // Clicks and keys may arrive after the group is gone
+  const change = useMountedCallback(onChange)
+  const index = options.findIndex(option => option.value === value)
+  const select = (offset: number): void => {
+    if (options.length > 0) {
+      // With nothing selected, the first key selects the first or last option
+      const next = index === -1 ? (offset > 0 ? 0 : options.length - 1) : (index + offset + options.length) % options.length
+      change(options[next].value)
+    }
+  }
+  const [previousKey, nextKey] = direction === 'horizontal' ? ['left', 'right'] : ['up', 'down']
+  useKeymap(focused === true
+    ? {
+        [previousKey]: { description: 'Previous option', action: () => select(-1) },
+        [nextKey]: { description: 'Next option', action: () => select(1) }
+      }
+    : {})
+
+  const box = direction === 'horizontal' ? intrinsics.hbox : intrinsics.vbox
+  return box({ role: 'list', value: options[index]?.label, focused: focused === true, gap: direction === 'horizontal' ? 2 : 0 },
+    ...options.map((option, i) => {
+      const isSelected = i === index
+      const isFocused = focused === true && (isSelected || (index === -1 && i === 0))
+      return intrinsics.hbox({ key: `option-${i}`, role: 'listitem', label: option.label, value: isSelected ? 'selected' : undefined, onClick: () => change(option.value) },
+        intrinsics.text({ style: Style.merge(itemStyle(isFocused, styles), isSelected ? Style.merge(DEFAULT_CHOICE_STYLES.selected, styles?.selected) : undefined) }, isSelected ? '(•)' : '( )'),
+        intrinsics.text({ style: itemStyle(isFocused, styles) }, ` ${option.label}`)
+      )
+    })
+  )
+}
+
+export interface SelectProps<T> {
+  options: Array<ChoiceOption<T>>
+  /** null if no option is selected */
+  value: T | null
+  onChange: (value: T) => void
+  /** Shown when no option is selected. Default is empty */
+  placeholder?: string
+  /** Whether space and enter open the list. The parent decides which control has focus, e.g. with {@link useFocus} */
+  focused?: boolean
+  /** Merged over the default styles, e.g. from a theme */
+  styles?: ChoiceStyles
+}
+
+/**
+ * The selected option's label and `▾`, which opens a list of the options below it when clicked, or on space or enter while focused.
+ * While the list is open, input only goes to it: up and down move the highlight, enter selects, escape closes.
+ * The list is drawn above the select's siblings, but not above its ancestors' siblings
+ */
+export function Select<T> ({ options, value, onChange, placeholder, focused, styles }: SelectProps<T>): VNode {
+  const renderer = getRenderer()
+  const isOpen = useState(false)
+  const highlighted = useState(0)
+  const index = options.findIndex(option => option.value === value)
+  // Called from the input capture, which is created when the list opens, so it must call the latest onChange
+  const change = useMountedCallback(onChange)
+
+  const open = (): void => {
+    highlighted.v = Math.max(0, index)
+    isOpen.v = true
+  }
+  const toggle = (): void => {
+    if (isOpen.v) {
+      isOpen.v = false
+    } else {
+      open()
+    }
+  }
+  const choose = (i: number): void => {
+    isOpen.v = false
+    if (options[i] !== undefined) {
+      change(options[i].value)
+    }
+  }
+  useKeymap(focused === true && !isOpen.v
+    ? {
+        space: { description: 'Open', action: open },
+        return: { description: 'Open', action: open }
+      }
+    : {})
+
+  useEffect(() => {
+    if (!isOpen.v) {
+      return
+    }
+    return renderer.captureInput(key => {
+      switch (key.name) {
+        case 'up':
+          highlighted.v = Math.max(0, highlighted.v - 1)
+          break
+        case 'down':
+          highlighted.v = Math.min(options.length - 1, highlighted.v + 1)
+          break
+        case 'space':
+        case 'return':
+        case 'enter':
+          choose(highlighted.v)
+          break
+        case 'escape':
+          isOpen.v = false
+          break
+      }
+    })
+  }, { onChange: [isOpen.v] })
+
+  const labelWidth = Math.max(Graphemes.lineWidth(placeholder ?? ''), ...options.map(option => Graphemes.lineWidth(option.label)))
+  const pad = (label: string): string => label + ' '.repeat(Math.max(0, labelWidth - Graphemes.lineWidth(label)))
+  const current = intrinsics.text({
+    key: 'current',
+    style: itemStyle(focused === true || isOpen.v, styles),
+    onClick: toggle
+  }, `${pad(options[index]?.label ?? placeholder ?? '')} ▾`)
+  if (!isOpen.v) {
+    return intrinsics.zbox({ role: 'menu', value: options[index]?.label, focused: focused === true }, current)
+  }
+  return intrinsics.zbox({ role: 'menu', value: options[index]?.label, focused: true, zIndex: SELECT_OPEN_Z_INDEX },
+    current,
+    intrinsics.zbox({ key: 'list', layout: 'local-absolute', y: 1 },
+      // Covers what's below the list
+      intrinsics.color({ key: 'background', width: labelWidth + 2, height: options.length, color: 'black' }),
+      intrinsics.vbox({ key: 'options' }, ...options.map((option, i) => intrinsics.text({
+        key: `option-${i}`,
+        role: 'menuitem',
+        value: i === index ? 'selected' : undefined,
+        style: Style.merge(itemStyle(i === highlighted.v, styles), i === index ? Style.merge(DEFAULT_CHOICE_STYLES.selected, styles?.selected) : undefined),
+        onClick: () => choose(i)
+      }, ` ${pad(option.label)} `)))
+    )
+  )
+}
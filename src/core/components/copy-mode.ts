@@ -0,0 +1,153 @@
+import { intrinsics, OVERLAY_Z_INDEX, placeholder, VNode } from 'core/view'
+import { getRenderer } from 'core/component'
+import { useEffect, useState } from 'core/hooks/intrinsic'
+import { KeySequence, useKeymap } from 'core/hooks/keymap'
+
+export interface CopyModeProps {
+  /** Enters copy mode. Default is `'M-c'` */
+  toggleKey?: KeySequence
+}
+
+interface Position {
+  x: number
+  y: number
+}
+
+/** What's selected between `anchor` and `cursor`, by row, as [start, end] columns (inclusive) */
+function selectedRanges (anchor: Position, cursor: Position, rectangular: boolean, width: number): Array<[number, number, number]> {
+  const top = Math.min(anchor.y, cursor.y)
+  const bottom = Math.max(anchor.y, cursor.y)
+  const ranges: Array<[number, number, number]> = []
+  for (let y = top; y <= bottom; y++) {
+    if (rectangular) {
+      ranges.push([y, Math.min(anchor.x, cursor.x), Math.max(anchor.x, cursor.x)])
+    } else {
+      const [start, end] = anchor.y < cursor.y || (anchor.y === cursor.y && anchor.x <= cursor.x) ? [anchor, cursor] : [cursor, anchor]
+      ranges.push([y, y === start.y ? start.x : 0, y === end.y ? end.x : width - 1])
+    }
+  }
+  return ranges
+}
+
+/**
+ * A keyboard-driven copy mode like tmux's: `toggleKey` takes the screen's text, then the arrow keys (or hjkl, 0 / home, end, g, G) move a cursor,
+ * `v` starts a selection (`r` toggles rectangular), `y` or enter copies it to the clipboard, and escape or q leaves.
+ * Only the text which is copied from is frozen: the app keeps rendering below the selection.
+ * While in copy mode, input only goes to copy mode. Put it directly in the root component, see {@link OVERLAY_Z_INDEX}
+ */
+export function CopyMode ({ toggleKey }: CopyModeProps = {}): VNode {
+  const renderer = getRenderer()
+  // The screen when copy mode was entered, or null if not in copy mode
+  const screen = useState<string[][] | null>(null)
+  const cursor = useState<Position>({ x: 0, y: 0 })
+  const anchor = useState<Position | null>(null)
+  const rectangular = useState(false)
+
+  useKeymap({
+    [toggleKey ?? 'M-c']: {
+      description: 'Copy mode',
+      action: () => {
+        const cells = renderer.getScreenCells()
+        cursor.v = { x: 0, y: Math.max(0, cells.length - 1) }
+        anchor.v = null
+        rectangular.v = false
+        screen.v = cells
+      }
+    }
+  })
+
+  const isActive = screen.v !== null
+  useEffect(() => {
+    if (!isActive) {
+      return
+    }
+    const height = (): number => screen.v?.length ?? 0
+    const width = (): number => Math.max(0, ...(screen.v ?? []).map(row => row.length))
+    const move = (x: number, y: number): void => {
+      cursor.v = { x: Math.max(0, Math.min(width() - 1, x)), y: Math.max(0, Math.min(height() - 1, y)) }
+    }
+    const copy = (): void => {
+      if (anchor.v !== null && screen.v !== null) {
+        const cells = screen.v
+        const lines = selectedRanges(anchor.v, cursor.v, rectangular.v, width())
+          .map(([y, start, end]) => cells[y].slice(start, end + 1).join('').trimEnd())
+        renderer.writeClipboard(lines.join('\n'))
+      }
+      screen.v = null
+    }
+    return renderer.captureInput(key => {
+      const { x, y } = cursor.v
+      switch (key.name) {
+        case 'left':
+        case 'h':
+          move(x - 1, y)
+          break
+        case 'right':
+        case 'l':
+          move(x + 1, y)
+          break
+        case 'up':
+        case 'k':
+          move(x, y - 1)
+          break
+        case 'down':
+        case 'j':
+          move(x, y + 1)
+          break
+        case '0':
+        case 'home':
+          move(0, y)
+          break
+        case 'end':
+          move(width() - 1, y)
+          break
+        case 'g':
+          move(x, key.shift === true ? height() - 1 : 0)
+          break
+        case 'v':
+        case 'space':
+          anchor.v = anchor.v === null ? cursor.v : null
+          break
+        case 'r':
+          rectangular.v = !rectangular.v
+          break
+        case 'y':
+        case 'return':
+        case 'enter':
+          copy()
+          break
+        case 'escape':
+        case 'q':
+          screen.v = null
+          break
+      }
+    })
+  }, { onChange: [isActive] })
+
+  if (screen.v === null) {
+    return placeholder('copy-mode')
+  }
+  const width = Math.max(0, ...screen.v.map(row => row.length))
+  const highlights = anchor.v === null
+    ? []
+    : selectedRanges(anchor.v, cursor.v, rectangular.v, width).map(([y, start, end]) => intrinsics.color({
+      key: `selection-${y}`,
+      layout: 'global-absolute',
+      x: start,
+      y,
+      width: end - start + 1,
+      height: 1,
+      color: { red: 0.3, green: 0.5, blue: 1, alpha: 0.5 }
+    }))
+  return intrinsics.zbox({ key: 'copy-mode', zIndex: OVERLAY_Z_INDEX, role: 'dialog', label: 'Copy mode' },
+    intrinsics.color({ key: 'cursor', layout: 'global-absolute', x: cursor.v.x, y: cursor.v.y, width: 1, height: 1, color: { red: 1, green: 1, blue: 1, alpha: 0.7 } }),
+    ...highlights,
+    intrinsics.text({
+      key: 'status',
+      layout: 'global-absolute',
+      x: 0,
+      y: Math.max(0, screen.v.length - 1),
+      style: { color: 'black', background: 'gold' }
+    }, ` COPY${anchor.v === null ? '' : rectangular.v ? ' (rectangle)' : ' (selecting)'} ${cursor.v.y + 1}:${cursor.v.x + 1} `)
+  )
+}
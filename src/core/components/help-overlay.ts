@@ -0,0 +1,91 @@
+import { intrinsics, OVERLAY_Z_INDEX, placeholder, VNode } from 'core/view'
+import { getRenderer, getVComponentPath } from 'core/component'
+import { useEffect, useState } from 'core/hooks/intrinsic'
+import { ActiveBinding, getActiveBindings, KeySequence, useKeymap } from 'core/hooks/keymap'
+import { getFocusedPath } from 'core/hooks/focus'
+import { Graphemes } from 'core/graphemes'
+
+export interface HelpOverlayProps {
+  /** Opens and closes the help. Default is `'?'` */
+  toggleKey?: KeySequence
+}
+
+/** Whether the binding is from the component with the focused focusable, or one of its descendants (e.g. a checkbox whose parent has the focus) */
+function isFromFocused (binding: ActiveBinding, focusedPath: string | null): boolean {
+  if (focusedPath === null) {
+    return false
+  }
+  return binding.path === focusedPath || binding.path.startsWith(`${focusedPath}/`)
+}
+
+/**
+ * Lists the key bindings with descriptions (see {@link useKeymap}) which are in use: first those of the focused widget (see {@link useFocus}),
+ * then the others. `toggleKey` opens it, and it, escape or q close it. While open, input only goes to it.
+ * Put it directly in the root component, see {@link OVERLAY_Z_INDEX}
+ */
+export function HelpOverlay ({ toggleKey }: HelpOverlayProps = {}): VNode {
+  const renderer = getRenderer()
+  const path = getVComponentPath()
+  const isOpen = useState(false)
+  const key = toggleKey ?? '?'
+
+  useKeymap({
+    [key]: { description: 'Help', action: () => { isOpen.v = true } }
+  })
+
+  useEffect(() => {
+    if (!isOpen.v) {
+      return
+    }
+    const [closeKey] = KeySequence.parse(key)
+    return renderer.captureInput(input => {
+      if (input.name === 'escape' || input.name === 'q' || KeySequence.matches(closeKey, input)) {
+        isOpen.v = false
+      }
+    })
+  }, { onChange: [isOpen.v] })
+
+  if (!isOpen.v) {
+    return placeholder('help-overlay')
+  }
+  const focusedPath = getFocusedPath(renderer)
+  const bindings = getActiveBindings(renderer).filter(binding => binding.path !== path)
+  const sections: Array<[string, ActiveBinding[]]> = [
+    ['Focused', bindings.filter(binding => isFromFocused(binding, focusedPath))],
+    ['Global', bindings.filter(binding => !isFromFocused(binding, focusedPath))]
+  ]
+  const sequenceWidth = Math.max(0, ...bindings.map(binding => Graphemes.lineWidth(binding.sequence)))
+  const lines = sections
+    .filter(([, sectionBindings]) => sectionBindings.length > 0)
+    .flatMap(([title, sectionBindings], index) => [
+      ...(index === 0 ? [] : ['']),
+      title,
+      ...sectionBindings.map(({ sequence, description }) => `  ${sequence}${' '.repeat(sequenceWidth - Graphemes.lineWidth(sequence))}  ${description}`)
+    ])
+  if (lines.length === 0) {
+    lines.push('No key bindings')
+  }
+
+  const viewport = renderer.getViewportSize()
+  // Leaves room for the border, and cuts off what doesn't fit
+  const maxLines = Math.max(1, viewport.height - 2)
+  const shownLines = lines.length > maxLines ? [...lines.slice(0, maxLines - 1), `… ${lines.length - maxLines + 1} more`] : lines
+  const width = Math.max(...shownLines.map(Graphemes.lineWidth)) + 4
+  const height = shownLines.length + 2
+  return intrinsics.zbox({
+    key: 'help-overlay',
+    zIndex: OVERLAY_Z_INDEX,
+    role: 'dialog',
+    label: 'Help',
+    layout: 'global-absolute',
+    x: Math.max(0, Math.floor((viewport.width - width) / 2)),
+    y: Math.max(0, Math.floor((viewport.height - height) / 2)),
+    width,
+    height
+  },
+  intrinsics.text({ key: 'bindings', x: 2, y: 1, style: { color: 'white' } }, shownLines.join('\n')),
+  intrinsics.border({ key: 'border', width, height, style: 'rounded', color: 'cyan' }),
+  // Covers what's below the help
+  intrinsics.color({ key: 'background', width, height, color: 'black' })
+  )
+}
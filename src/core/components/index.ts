@@ -0,0 +1,12 @@
+export * from 'core/components/breadcrumbs'
+export * from 'core/components/wizard-steps'
+export * from 'core/components/copy-mode'
+export * from 'core/components/search-overlay'
+export * from 'core/components/choice'
+export * from 'core/components/button'
+export * from 'core/components/scroll-view'
+export * from 'core/components/list'
+export * from 'core/components/help-overlay'
+export * from 'core/components/text-input'
+export * from 'core/components/text-area'
+export * from 'core/components/link'
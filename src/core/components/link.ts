@@ -0,0 +1,46 @@
+import { intrinsics, Style, VNode } from 'core/view'
+import { useFocus } from 'core/hooks/focus'
+import { useKeymap } from 'core/hooks/keymap'
+
+export interface LinkProps {
+  /** See {@link TextAttrs.link} */
+  url: string
+  /** Default is the URL */
+  label?: string
+  /** Called with the URL when enter is pressed while the link is focused, e.g. to open it or navigate within the app */
+  onActivate?: (url: string) => void
+  /** Disabled links are skipped by Tab. Default is true */
+  enabled?: boolean
+  /** Focus when created. Default is false */
+  autoFocus?: boolean
+  /** Merged over the default styles, e.g. from a theme */
+  styles?: LinkStyles
+}
+
+export interface LinkStyles {
+  normal?: Style
+  /** Merged over `normal` while focused */
+  focused?: Style
+}
+
+const DEFAULT_LINK_STYLES: Required<LinkStyles> = {
+  normal: { color: 'blue', underline: true },
+  focused: { color: 'cyan', bold: true }
+}
+
+/**
+ * Text with the `link` attribute, which is also focusable (see {@link useFocus}), so it can be activated from the keyboard:
+ * enter calls `onActivate` while it's focused. Clicking it still follows the link where the platform supports it
+ */
+export function Link ({ url, label, onActivate, enabled, autoFocus, styles }: LinkProps): VNode {
+  const focus = useFocus({ enabled: enabled ?? true, autoFocus })
+  const text = label ?? url
+  useKeymap(focus.isFocused && onActivate !== undefined
+    ? { return: { description: `Open ${text}`, action: () => onActivate(url) } }
+    : {})
+
+  const style = focus.isFocused
+    ? Style.merge(DEFAULT_LINK_STYLES.normal, styles?.normal, DEFAULT_LINK_STYLES.focused, styles?.focused)
+    : Style.merge(DEFAULT_LINK_STYLES.normal, styles?.normal)
+  return intrinsics.text({ role: 'link', label: text, link: url, focus, style }, text)
+}
@@ -0,0 +1,139 @@
+import { intrinsics, Measurement, Style, VNode } from 'core/view'
+import { useEffect, useState } from 'core/hooks/intrinsic'
+import { useKeymap } from 'core/hooks/keymap'
+import { ScrollView, ScrollViewStyles } from 'core/components/scroll-view'
+
+/** `'none'` only highlights, `'single'` selects the highlighted item, `'multi'` toggles items in and out of the selection */
+export type SelectionMode = 'none' | 'single' | 'multi'
+
+export interface ListItemState {
+  /** The item which the keys act on. Only one item is highlighted, and only while the list is focused */
+  isHighlighted: boolean
+  isSelected: boolean
+}
+
+export interface ListProps<T> {
+  items: T[]
+  /** Default is the item's text (`String(item)`), styled by whether it's highlighted and selected */
+  renderItem?: (item: T, index: number, state: ListItemState) => VNode
+  /** In rows. Default is 1 */
+  itemHeight?: number | ((item: T, index: number) => number)
+  /** Of the list, in rows. The list scrolls if the items are taller */
+  height: number
+  /** Default is the parent's width */
+  width?: Measurement
+  /** Default is 'single' */
+  selectionMode?: SelectionMode
+  /** Selected items, compared with `===`. Ignored if `selectionMode` is 'none' */
+  selected?: T[]
+  /** Called with the new selection when space or a click changes it (or in single mode, enter) */
+  onSelect?: (selected: T[]) => void
+  /** Called when enter is pressed on an item */
+  onActivate?: (item: T, index: number) => void
+  /** Whether the keys move the highlight. The parent decides which control has focus, e.g. with {@link useFocus} */
+  focused?: boolean
+  /** Merged over the default styles, e.g. from a theme */
+  styles?: ListStyles
+}
+
+export interface ListStyles {
+  item?: Style
+  /** Merged over `item` */
+  highlighted?: Style
+  /** Merged over `item` (and `highlighted`) */
+  selected?: Style
+  scrollbar?: ScrollViewStyles
+}
+
+const DEFAULT_LIST_STYLES: Required<Omit<ListStyles, 'scrollbar'>> = {
+  item: { color: 'white' },
+  highlighted: { color: 'black', background: 'cyan' },
+  selected: { color: 'green', bold: true }
+}
+
+/**
+ * A scrolling list of items (see {@link ScrollView}), with a highlighted item and a selection.
+ * While focused, up and down (and page up / down, home and end) move the highlight, space selects or toggles the highlighted item,
+ * and enter activates it. Clicking an item highlights and selects it
+ */
+export function List<T> ({ items, renderItem, itemHeight, height, width, selectionMode, selected, onSelect, onActivate, focused, styles }: ListProps<T>): VNode {
+  const mode = selectionMode ?? 'single'
+  const highlighted = useState(0)
+  // Items may have been removed since the last move
+  const highlightedIndex = Math.max(0, Math.min(items.length - 1, highlighted.v))
+  useEffect(() => {
+    if (highlighted.v !== highlightedIndex && items.length > 0) {
+      highlighted.v = highlightedIndex
+    }
+  }, { onChange: [highlightedIndex] })
+
+  const isSelected = (item: T): boolean => mode !== 'none' && (selected ?? []).includes(item)
+  const select = (index: number): void => {
+    const item = items[index]
+    if (item === undefined || mode === 'none') {
+      return
+    }
+    if (mode === 'single') {
+      onSelect?.([item])
+    } else {
+      onSelect?.(isSelected(item) ? (selected ?? []).filter(other => other !== item) : [...(selected ?? []), item])
+    }
+  }
+  const move = (index: number): void => {
+    if (items.length > 0) {
+      highlighted.v = Math.max(0, Math.min(items.length - 1, index))
+    }
+  }
+  const page = Math.max(1, height - 1)
+  useKeymap(focused === true
+    ? {
+        up: { description: 'Previous item', action: () => move(highlightedIndex - 1) },
+        down: { description: 'Next item', action: () => move(highlightedIndex + 1) },
+        pageup: { description: 'Previous page', action: () => move(highlightedIndex - page) },
+        pagedown: { description: 'Next page', action: () => move(highlightedIndex + page) },
+        home: { description: 'First item', action: () => move(0) },
+        end: { description: 'Last item', action: () => move(items.length - 1) },
+        ...(mode === 'none' ? {} : { space: { description: mode === 'multi' ? 'Toggle selection' : 'Select', action: () => select(highlightedIndex) } }),
+        return: {
+          description: 'Activate',
+          action: () => {
+            if (items[highlightedIndex] !== undefined) {
+              if (mode === 'single') {
+                select(highlightedIndex)
+              }
+              onActivate?.(items[highlightedIndex], highlightedIndex)
+            }
+          }
+        }
+      }
+    : {})
+
+  return intrinsics.zbox({ role: 'list', focused: focused === true, width: width ?? '100%', height },
+    ScrollView({
+      items,
+      itemHeight: itemHeight ?? 1,
+      height,
+      visibleIndex: highlightedIndex,
+      styles: styles?.scrollbar,
+      renderItem: (item, index) => {
+        const state: ListItemState = { isHighlighted: focused === true && index === highlightedIndex, isSelected: isSelected(item) }
+        const style = Style.merge(
+          DEFAULT_LIST_STYLES.item,
+          styles?.item,
+          state.isHighlighted ? Style.merge(DEFAULT_LIST_STYLES.highlighted, styles?.highlighted) : undefined,
+          state.isSelected ? Style.merge(DEFAULT_LIST_STYLES.selected, styles?.selected) : undefined
+        )
+        return intrinsics.zbox({
+          key: 'item',
+          role: 'listitem',
+          value: state.isSelected ? 'selected' : undefined,
+          width: '100%',
+          onClick: () => {
+            move(index)
+            select(index)
+          }
+        }, renderItem?.(item, index, state) ?? intrinsics.text({ style, wrapMode: 'clip' }, String(item)))
+      }
+    })
+  )
+}
@@ -0,0 +1,150 @@
+import { intrinsics, Measurement, Style, VNode } from 'core/view'
+import { getVComponent, VComponent } from 'core/component'
+import { useEffect, useState, useStateFast } from 'core/hooks/intrinsic'
+import { useKeymap } from 'core/hooks/keymap'
+import { getFocusedIndex, useScrollContainer } from 'core/hooks/focus'
+
+export interface ScrollViewProps<T> {
+  items: T[]
+  renderItem: (item: T, index: number) => VNode
+  /** In rows. Each item is laid out in a box of this height, so items outside of the viewport are known without rendering them */
+  itemHeight: number | ((item: T, index: number) => number)
+  /** Of the viewport, in rows */
+  height: number
+  /** Of the viewport, including the scrollbar. Default is the parent's width */
+  width?: Measurement
+  /** Whether the arrow keys, page up / down, home and end scroll. The parent decides which control has focus, e.g. with {@link useFocus} */
+  focused?: boolean
+  /**
+   * Whether the item is a sticky header, e.g. of a group. The last sticky item scrolled past is pinned to the top of the viewport,
+   * until the next one pushes it up
+   */
+  isSticky?: (item: T, index: number) => boolean
+  /** Scrolls just enough to show this item whenever it changes, e.g. the highlighted item of a list */
+  visibleIndex?: number
+  /** Called with the new offset (the number of rows scrolled past) when it changes */
+  onScroll?: (offset: number) => void
+  /** Merged over the default styles, e.g. from a theme */
+  styles?: ScrollViewStyles
+}
+
+export interface ScrollViewStyles {
+  track?: Style
+  thumb?: Style
+}
+
+const DEFAULT_SCROLL_VIEW_STYLES: Required<ScrollViewStyles> = {
+  track: { color: 'gray' },
+  thumb: { color: 'white' }
+}
+
+/** Rows scrolled per wheel tick */
+const WHEEL_ROWS = 3
+
+/**
+ * Shows the items which are in a viewport `height` rows tall, and a scrollbar when they don't all fit.
+ * Only visible items are rendered, so long lists are cheap. Scrolls with the mouse wheel,
+ * and while focused, with the arrow keys (a row), page up / down (a viewport) and home / end.
+ * Each item is its own component, and when something in it is focused (see {@link useFocus}), it's scrolled into view.
+ * The items just above and below the viewport are also rendered (but clipped), so Tab moves into them and scrolls,
+ * and the item with focus stays rendered while it's scrolled out of view, so scrolling doesn't take its focus.
+ * Sticky items (see `isSticky`) stay at the top of the viewport while their section is scrolled through, like CSS `position: sticky`
+ */
+export function ScrollView<T> ({ items, renderItem, itemHeight, height, width, isSticky, visibleIndex, focused, onScroll, styles }: ScrollViewProps<T>): VNode {
+  const offset = useState(0)
+  // Distinguishes the item components from those of other scroll views in the same component
+  const [instance] = useStateFast(getVComponent().nextStateIndex)
+  // Identifies this scroll view's items to the focus system
+  const [group] = useStateFast({})
+
+  // Where each item starts, and where the last one ends
+  const tops = [0]
+  items.forEach((item, index) => {
+    tops.push(tops[index] + (typeof itemHeight === 'function' ? itemHeight(item, index) : itemHeight))
+  })
+  const contentHeight = tops[items.length]
+  const maxOffset = Math.max(0, contentHeight - height)
+  // Items may have been removed since the last scroll
+  const currentOffset = Math.min(offset.v, maxOffset)
+
+  const scrollTo = (newOffset: number): void => {
+    newOffset = Math.max(0, Math.min(maxOffset, Math.round(newOffset)))
+    if (newOffset !== offset.v) {
+      offset.v = newOffset
+      onScroll?.(newOffset)
+    }
+  }
+  const scrollIntoView = (index: number): void => {
+    if (index >= 0 && index < items.length) {
+      // Shows the bottom of the item, then the top if it's taller than the viewport
+      scrollTo(Math.min(Math.max(currentOffset, tops[index + 1] - height), tops[index]))
+    }
+  }
+  useEffect(() => {
+    if (visibleIndex !== undefined) {
+      scrollIntoView(visibleIndex)
+    }
+  }, { onChange: [visibleIndex] })
+
+  const page = Math.max(1, height - 1)
+  useKeymap(focused === true
+    ? {
+        up: { description: 'Scroll up', action: () => scrollTo(currentOffset - 1) },
+        down: { description: 'Scroll down', action: () => scrollTo(currentOffset + 1) },
+        pageup: { description: 'Page up', action: () => scrollTo(currentOffset - page) },
+        pagedown: { description: 'Page down', action: () => scrollTo(currentOffset + page) },
+        home: { description: 'Scroll to top', action: () => scrollTo(0) },
+        end: { description: 'Scroll to bottom', action: () => scrollTo(maxOffset) }
+      }
+    : {})
+
+  const visibleIndices = items.map((_, index) => index).filter(index => tops[index + 1] > currentOffset && tops[index] < currentOffset + height)
+  const focusedIndex = getFocusedIndex(group)
+  const isRendered = (index: number): boolean =>
+    index === focusedIndex || (visibleIndices.length > 0 && index >= visibleIndices[0] - 1 && index <= visibleIndices[visibleIndices.length - 1] + 1)
+  // The last sticky item scrolled past, and the next sticky item, which pushes it up once they meet
+  const stickyIndices = isSticky === undefined ? [] : items.map((_, index) => index).filter(index => isSticky(items[index], index))
+  const pinnedIndex = stickyIndices.filter(index => tops[index] <= currentOffset).pop() ?? null
+  const nextStickyIndex = stickyIndices.find(index => tops[index] > currentOffset) ?? null
+  const renderAt = (index: number, y: number): VNode =>
+    intrinsics.zbox({ key: `item-${index}`, layout: 'local-absolute', y, height: tops[index + 1] - tops[index] },
+      VComponent(`ScrollViewItem${instance}-${index}`, { item: items[index], index }, props => {
+        useScrollContainer(() => scrollIntoView(props.index), { group, index: props.index })
+        return renderItem(props.item, props.index)
+      })
+    )
+  const visible: VNode[] = []
+  if (pinnedIndex !== null) {
+    const pinnedHeight = tops[pinnedIndex + 1] - tops[pinnedIndex]
+    const nextTop = nextStickyIndex === null ? Infinity : tops[nextStickyIndex] - currentOffset
+    // First, so it's drawn above the items scrolling under it
+    visible.push(renderAt(pinnedIndex, Math.min(0, nextTop - pinnedHeight)))
+  }
+  items.forEach((_, index) => {
+    if (index !== pinnedIndex && isRendered(index)) {
+      visible.push(renderAt(index, tops[index] - currentOffset))
+    }
+  })
+
+  const hasScrollbar = contentHeight > height
+  const children = [
+    intrinsics.zbox({ key: 'items', clip: true, width: hasScrollbar ? '100% - 1' : '100%', height }, ...visible)
+  ]
+  if (hasScrollbar) {
+    const thumbHeight = Math.max(1, Math.round(height * height / contentHeight))
+    const thumbTop = Math.round((height - thumbHeight) * currentOffset / maxOffset)
+    // The thumb is first, so it's drawn above the track
+    children.push(intrinsics.text({ key: 'thumb', layout: 'local-absolute', x: '100% - 1', y: thumbTop, style: Style.merge(DEFAULT_SCROLL_VIEW_STYLES.thumb, styles?.thumb) },
+      Array(thumbHeight).fill('┃').join('\n')))
+    children.push(intrinsics.text({ key: 'track', layout: 'local-absolute', x: '100% - 1', style: Style.merge(DEFAULT_SCROLL_VIEW_STYLES.track, styles?.track) },
+      Array(height).fill('│').join('\n')))
+  }
+  return intrinsics.zbox({
+    role: 'group',
+    value: `${currentOffset}/${maxOffset}`,
+    focused: focused === true,
+    width: width ?? '100%',
+    height,
+    onWheel: event => scrollTo(currentOffset + (event.type === 'wheel-up' ? -WHEEL_ROWS : WHEEL_ROWS))
+  }, ...children)
+}
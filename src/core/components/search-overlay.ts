@@ -0,0 +1,147 @@
+import { intrinsics, OVERLAY_Z_INDEX, placeholder, VNode } from 'core/view'
+import { getRenderer } from 'core/component'
+import { useEffect, useState } from 'core/hooks/intrinsic'
+import { KeySequence, useKeymap } from 'core/hooks/keymap'
+import { Graphemes } from 'core/graphemes'
+
+/** Where a query was found on screen, in columns and rows */
+export interface SearchMatch {
+  x: number
+  y: number
+  /** In columns */
+  width: number
+}
+
+export interface SearchOverlayProps {
+  /** Opens the search prompt. Default is `'/'` */
+  openKey?: KeySequence
+  /** Called when a match becomes the current one, e.g. to scroll or focus the view containing it */
+  onMatch?: (match: SearchMatch) => void
+}
+
+/**
+ * Finds `query` in `cells` (see {@link Renderer.getScreenCells}), top to bottom and left to right.
+ * Case-insensitive unless `query` has an uppercase letter, like Vim's smartcase. Matches don't span rows
+ */
+export function searchScreen (cells: string[][], query: string): SearchMatch[] {
+  if (query === '') {
+    return []
+  }
+  const caseSensitive = query !== query.toLowerCase()
+  const normalize = (text: string): string => caseSensitive ? text : text.toLowerCase()
+  const needle = normalize(query)
+  const matches: SearchMatch[] = []
+  cells.forEach((row, y) => {
+    // The column of each character in the row's text, and one past the end
+    const columns: number[] = []
+    let text = ''
+    row.forEach((cell, x) => {
+      for (let i = 0; i < cell.length; i++) {
+        columns.push(x)
+      }
+      text += cell
+    })
+    columns.push(row.length)
+    const haystack = normalize(text)
+    for (let index = haystack.indexOf(needle); index !== -1; index = haystack.indexOf(needle, index + 1)) {
+      const x = columns[index]
+      matches.push({ x, y, width: columns[index + needle.length] - x })
+    }
+  })
+  return matches
+}
+
+/**
+ * Searches what's on screen, like `/` in less: `openKey` opens a prompt, typing searches and highlights matches,
+ * enter closes the prompt and keeps the highlights, then `n` and `N` go to the next and previous match, and escape clears.
+ * While the prompt is open, input only goes to it. Put it directly in the root component, see {@link OVERLAY_Z_INDEX}
+ */
+export function SearchOverlay ({ openKey, onMatch }: SearchOverlayProps = {}): VNode {
+  const renderer = getRenderer()
+  // null if not searching
+  const query = useState<string | null>(null)
+  const isTyping = useState(false)
+  const matches = useState<SearchMatch[]>([])
+  const current = useState(0)
+
+  const select = (index: number): void => {
+    if (matches.v.length > 0) {
+      current.v = (index + matches.v.length) % matches.v.length
+      onMatch?.(matches.v[current.v])
+    }
+  }
+  const close = (): void => {
+    query.v = null
+    isTyping.v = false
+    matches.v = []
+  }
+
+  useKeymap({
+    [openKey ?? '/']: {
+      description: 'Search',
+      action: () => {
+        query.v = ''
+        isTyping.v = true
+        matches.v = []
+      }
+    },
+    // Only while there are highlights, so they don't shadow the app's bindings
+    ...(query.v === null
+      ? {}
+      : {
+          n: { description: 'Next match', action: () => select(current.v + 1) },
+          N: { description: 'Previous match', action: () => select(current.v - 1) },
+          escape: { description: 'Clear search', action: close }
+        })
+  })
+
+  useEffect(() => {
+    if (!isTyping.v) {
+      return
+    }
+    // Search the screen without the overlay
+    const cells = renderer.getScreenCells()
+    return renderer.captureInput(key => {
+      if (key.name === 'escape') {
+        close()
+      } else if (key.name === 'return' || key.name === 'enter') {
+        isTyping.v = false
+      } else {
+        const text = query.v ?? ''
+        if (key.name === 'backspace') {
+          query.v = Graphemes.removeBefore(text, Graphemes.count(text))
+        } else if (key.ctrl !== true && key.meta !== true && key.sequence !== undefined && Graphemes.isSingle(key.sequence) && key.sequence >= ' ') {
+          query.v = text + key.sequence
+        } else {
+          return
+        }
+        matches.v = searchScreen(cells, query.v)
+        select(0)
+      }
+    })
+  }, { onChange: [isTyping.v] })
+
+  if (query.v === null) {
+    return placeholder('search-overlay')
+  }
+  return intrinsics.zbox({ key: 'search-overlay', zIndex: OVERLAY_Z_INDEX, role: 'dialog', label: 'Search' },
+    ...matches.v.map((match, index) => intrinsics.color({
+      key: `match-${index}`,
+      layout: 'global-absolute',
+      x: match.x,
+      y: match.y,
+      width: match.width,
+      height: 1,
+      color: index === current.v ? { red: 1, green: 0.6, blue: 0, alpha: 0.7 } : { red: 1, green: 0.9, blue: 0, alpha: 0.4 }
+    })),
+    intrinsics.text({
+      key: 'prompt',
+      layout: 'global-absolute',
+      x: 0,
+      // The bottom row, which follows resizes
+      y: '100vh - 1',
+      style: { color: 'white', background: 'black' },
+      cursor: isTyping.v ? { x: 1 + Graphemes.lineWidth(query.v), y: 0 } : undefined
+    }, `/${query.v}${matches.v.length === 0 ? (query.v === '' ? '' : '  (no matches)') : `  (${current.v + 1}/${matches.v.length})`}`)
+  )
+}
@@ -0,0 +1,198 @@
+import { intrinsics, Style, VNode } from 'core/view'
+import { useState, useStateFast } from 'core/hooks/intrinsic'
+import { useInput, usePaste } from 'core/hooks/extra'
+import { useFocus } from 'core/hooks/focus'
+import { Graphemes } from 'core/graphemes'
+import { TextEdit, TextInputStyles } from 'core/components/text-input'
+
+export interface TextAreaProps {
+  value: string
+  onChange: (value: string) => void
+  /** Shown while the value is empty */
+  placeholder?: string
+  /** In columns. Lines are word-wrapped to fit. Default is 40 */
+  width?: number
+  /** In rows. The area grows with its text up to this, then scrolls vertically to keep the cursor visible. Default is 5 */
+  maxHeight?: number
+  /** Disabled areas can't be edited, and are skipped by Tab. Default is true */
+  enabled?: boolean
+  /** Focus when created. Default is false */
+  autoFocus?: boolean
+  /** Merged over the default styles, e.g. from a theme */
+  styles?: TextInputStyles
+}
+
+const DEFAULT_TEXT_AREA_STYLES: Required<TextInputStyles> = {
+  text: { color: 'white', background: 'black' },
+  focused: { background: 'blue' },
+  placeholder: { color: 'gray' }
+}
+
+/** A wrapped line, as grapheme indices into the text: `start` inclusive and `end` exclusive, without the newline */
+interface VisualLine {
+  start: number
+  end: number
+  /** Whether the next line continues this one without a newline, so `end` is the start of the next line */
+  isWrapped: boolean
+}
+
+/** The last cursor position on the line. On a wrapped line it's before the last grapheme, since `end` is at the start of the next line */
+function lineEnd (line: VisualLine): number {
+  return line.isWrapped ? line.end - 1 : line.end
+}
+
+/** Splits at newlines, then word-wraps each line to `width` columns, breaking inside words which don't fit on a line */
+function layoutLines (graphemes: string[], width: number): VisualLine[] {
+  const lines: VisualLine[] = []
+  const wrap = (start: number, end: number): void => {
+    while (true) {
+      let lineWidth = 0
+      let i = start
+      // After the last space which fits, so the space stays at the end of the line
+      let lastBreak = -1
+      while (i < end && lineWidth + Graphemes.width(graphemes[i]) <= width) {
+        if (graphemes[i] === ' ') {
+          lastBreak = i + 1
+        }
+        lineWidth += Graphemes.width(graphemes[i])
+        i++
+      }
+      if (i >= end) {
+        lines.push({ start, end, isWrapped: false })
+        return
+      }
+      const breakAt = lastBreak > start ? lastBreak : Math.max(i, start + 1)
+      lines.push({ start, end: breakAt, isWrapped: true })
+      start = breakAt
+    }
+  }
+  let lineStart = 0
+  graphemes.forEach((grapheme, i) => {
+    if (grapheme === '\n') {
+      wrap(lineStart, i)
+      lineStart = i + 1
+    }
+  })
+  wrap(lineStart, graphemes.length)
+  return lines
+}
+
+/** The line the cursor is on. At a wrap, that's the start of the next line */
+function rowOf (lines: VisualLine[], cursor: number): number {
+  let row = 0
+  lines.forEach((line, i) => {
+    if (line.start <= cursor) {
+      row = i
+    }
+  })
+  return row
+}
+
+/** The grapheme index in `line` whose column is closest to `column` */
+function indexAtColumn (graphemes: string[], line: VisualLine, column: number): number {
+  let lineWidth = 0
+  for (let i = line.start; i < line.end; i++) {
+    const width = Graphemes.width(graphemes[i])
+    if (lineWidth + width / 2 >= column) {
+      return i
+    }
+    lineWidth += width
+  }
+  return lineEnd(line)
+}
+
+/**
+ * A multi-line text input, which is focusable (see {@link useFocus}). Like {@link TextInput}, but enter inserts a newline,
+ * text is word-wrapped, up and down move the cursor between wrapped lines (keeping its column), home and end go to the start and end of the line,
+ * and it scrolls vertically past `maxHeight`
+ */
+export function TextArea ({ value, onChange, placeholder, width, maxHeight, enabled, autoFocus, styles }: TextAreaProps): VNode {
+  const isEnabled = enabled ?? true
+  const focus = useFocus({ enabled: isEnabled, autoFocus, editsText: true })
+  const cursor = useState(Graphemes.count(value))
+  // First row shown, and the column up and down keep, mutated without updating the component
+  const [view] = useStateFast<{ scroll: number, column: number | null }>({ scroll: 0, column: null })
+  const columns = width ?? 40
+  const maxRows = Math.max(1, maxHeight ?? 5)
+
+  const graphemes = Graphemes.split(value)
+  // The value may have changed from outside
+  const currentCursor = Math.min(cursor.v, graphemes.length)
+  // One column is left for the cursor after the last grapheme
+  const lines = layoutLines(graphemes, Math.max(1, columns - 1))
+  const row = rowOf(lines, currentCursor)
+  const column = Graphemes.lineWidth(graphemes.slice(lines[row].start, currentCursor).join(''))
+
+  const edit = (result: TextEdit): void => {
+    view.column = null
+    cursor.v = result.cursor
+    if (result.text !== value) {
+      onChange(result.text)
+    }
+  }
+  const moveToRow = (targetRow: number): void => {
+    view.column = view.column ?? column
+    cursor.v = targetRow < 0 ? 0 : targetRow >= lines.length ? graphemes.length : indexAtColumn(graphemes, lines[targetRow], view.column)
+  }
+  useInput(key => {
+    if (!focus.isFocused) {
+      return
+    }
+    switch (key.name) {
+      case 'up':
+        moveToRow(row - 1)
+        break
+      case 'down':
+        moveToRow(row + 1)
+        break
+      case 'home':
+        edit({ text: value, cursor: lines[row].start })
+        break
+      case 'end':
+        edit({ text: value, cursor: lineEnd(lines[row]) })
+        break
+      case 'return':
+      case 'enter':
+        edit(TextEdit.insert({ text: value, cursor: currentCursor }, '\n'))
+        break
+      default: {
+        const result = TextEdit.applyKey({ text: value, cursor: currentCursor }, key)
+        if (result !== null) {
+          edit(result)
+        }
+      }
+    }
+  })
+  usePaste(text => {
+    if (focus.isFocused) {
+      edit(TextEdit.insert({ text: value, cursor: currentCursor }, text.replace(/\r\n?/g, '\n')))
+    }
+  })
+
+  const height = Math.min(lines.length, maxRows)
+  // Scroll so the cursor's line is visible
+  view.scroll = Math.max(Math.min(view.scroll, row, lines.length - height), row - height + 1, 0)
+
+  const isPlaceholder = value === '' && placeholder !== undefined
+  const style = Style.merge(
+    DEFAULT_TEXT_AREA_STYLES.text,
+    styles?.text,
+    focus.isFocused ? Style.merge(DEFAULT_TEXT_AREA_STYLES.focused, styles?.focused) : undefined,
+    isPlaceholder ? Style.merge(DEFAULT_TEXT_AREA_STYLES.placeholder, styles?.placeholder) : undefined
+  )
+  const shownLines = isPlaceholder
+    ? [placeholder]
+    : lines.slice(view.scroll, view.scroll + height).map(line => graphemes.slice(line.start, line.end).join(''))
+  return intrinsics.text({
+    role: 'textbox',
+    value,
+    label: placeholder,
+    focus,
+    dim: isEnabled ? undefined : 0.5,
+    style,
+    wrapMode: 'clip',
+    width: columns,
+    height,
+    cursor: focus.isFocused ? { x: column, y: row - view.scroll } : undefined
+  }, shownLines.map(line => line + ' '.repeat(Math.max(0, columns - Graphemes.lineWidth(line)))).join('\n'))
+}
@@ -0,0 +1,157 @@
+import { Key } from '@raycenity/misc-ts'
+import { intrinsics, Style, VNode } from 'core/view'
+import { useState, useStateFast } from 'core/hooks/intrinsic'
+import { useInput, usePaste } from 'core/hooks/extra'
+import { useFocus } from 'core/hooks/focus'
+import { Graphemes } from 'core/graphemes'
+
+export interface TextInputProps {
+  value: string
+  onChange: (value: string) => void
+  /** Called when enter is pressed */
+  onSubmit?: (value: string) => void
+  /** Shown while the value is empty */
+  placeholder?: string
+  /** Shown in place of each character, e.g. `'*'` for a password */
+  mask?: string
+  /** In columns. The text scrolls horizontally to keep the cursor visible. Default is 20 */
+  width?: number
+  /** Disabled inputs can't be edited, and are skipped by Tab. Default is true */
+  enabled?: boolean
+  /** Focus when created. Default is false */
+  autoFocus?: boolean
+  /** Merged over the default styles, e.g. from a theme */
+  styles?: TextInputStyles
+}
+
+export interface TextInputStyles {
+  text?: Style
+  /** Merged over `text` while focused */
+  focused?: Style
+  /** Merged over `text` for the placeholder */
+  placeholder?: Style
+}
+
+const DEFAULT_TEXT_INPUT_STYLES: Required<TextInputStyles> = {
+  text: { color: 'white', background: 'black' },
+  focused: { background: 'blue' },
+  placeholder: { color: 'gray' }
+}
+
+/** Text being edited, with the cursor as a grapheme index, see {@link Graphemes.insert} */
+export interface TextEdit {
+  text: string
+  cursor: number
+}
+
+export module TextEdit {
+  /**
+   * Applies an editing key: typed characters, backspace, delete, left, right, and on a single line, home and end.
+   * Returns null if the key isn't an editing key. Shared by text widgets, so they edit graphemes the same way
+   */
+  export function applyKey ({ text, cursor }: TextEdit, key: Key): TextEdit | null {
+    const length = Graphemes.count(text)
+    switch (key.name) {
+      case 'left':
+        return { text, cursor: Math.max(0, cursor - 1) }
+      case 'right':
+        return { text, cursor: Math.min(length, cursor + 1) }
+      case 'backspace':
+        return { text: Graphemes.removeBefore(text, cursor), cursor: Math.max(0, cursor - 1) }
+      case 'delete':
+        return { text: Graphemes.removeAt(text, cursor), cursor }
+    }
+    if (key.ctrl !== true && key.meta !== true && key.sequence !== undefined && Graphemes.isSingle(key.sequence) && key.sequence >= ' ') {
+      return insert({ text, cursor }, key.sequence)
+    }
+    return null
+  }
+
+  /** Inserts `inserted` at the cursor, and moves the cursor after it */
+  export function insert ({ text, cursor }: TextEdit, inserted: string): TextEdit {
+    return { text: Graphemes.insert(text, cursor, inserted), cursor: cursor + Graphemes.count(inserted) }
+  }
+}
+
+/**
+ * A single-line text input, which is focusable (see {@link useFocus}). While focused, typing and pasting insert text,
+ * left, right, home and end move the cursor, backspace and delete remove text, and enter calls `onSubmit`.
+ * Editing, the cursor and scrolling work on graphemes and their widths, so emoji and CJK text are edited and laid out correctly
+ */
+export function TextInput ({ value, onChange, onSubmit, placeholder, mask, width, enabled, autoFocus, styles }: TextInputProps): VNode {
+  const isEnabled = enabled ?? true
+  const focus = useFocus({ enabled: isEnabled, autoFocus, editsText: true })
+  const cursor = useState(Graphemes.count(value))
+  // Index of the first grapheme shown, mutated without updating the component
+  const [scroll] = useStateFast({ index: 0 })
+  const columns = width ?? 20
+
+  // The value may have changed from outside
+  const length = Graphemes.count(value)
+  const currentCursor = Math.min(cursor.v, length)
+  const edit = (result: TextEdit): void => {
+    cursor.v = result.cursor
+    if (result.text !== value) {
+      onChange(result.text)
+    }
+  }
+  useInput(key => {
+    if (!focus.isFocused) {
+      return
+    }
+    if (key.name === 'return' || key.name === 'enter') {
+      onSubmit?.(value)
+    } else if (key.name === 'home') {
+      cursor.v = 0
+    } else if (key.name === 'end') {
+      cursor.v = length
+    } else {
+      const result = TextEdit.applyKey({ text: value, cursor: currentCursor }, key)
+      if (result !== null) {
+        edit(result)
+      }
+    }
+  })
+  usePaste(text => {
+    if (focus.isFocused) {
+      // Single line, so only the first line is inserted
+      edit(TextEdit.insert({ text: value, cursor: currentCursor }, text.split(/\r?\n/)[0]))
+    }
+  })
+
+  const graphemes = Graphemes.split(value).map(grapheme => mask ?? grapheme)
+  // Scroll so the cursor is visible, leaving a column for it after the last grapheme
+  scroll.index = Math.min(scroll.index, currentCursor)
+  while (Graphemes.lineWidth(graphemes.slice(scroll.index, currentCursor).join('')) > columns - 1) {
+    scroll.index++
+  }
+  const shown: string[] = []
+  let shownWidth = 0
+  for (const grapheme of graphemes.slice(scroll.index)) {
+    if (shownWidth + Graphemes.width(grapheme) > columns) {
+      break
+    }
+    shown.push(grapheme)
+    shownWidth += Graphemes.width(grapheme)
+  }
+
+  const isPlaceholder = value === '' && placeholder !== undefined
+  const style = Style.merge(
+    DEFAULT_TEXT_INPUT_STYLES.text,
+    styles?.text,
+    focus.isFocused ? Style.merge(DEFAULT_TEXT_INPUT_STYLES.focused, styles?.focused) : undefined,
+    isPlaceholder ? Style.merge(DEFAULT_TEXT_INPUT_STYLES.placeholder, styles?.placeholder) : undefined
+  )
+  const text = isPlaceholder ? Graphemes.split(placeholder).slice(0, columns).join('') : shown.join('')
+  return intrinsics.text({
+    role: 'textbox',
+    value: mask === undefined ? value : undefined,
+    label: placeholder,
+    focus,
+    dim: isEnabled ? undefined : 0.5,
+    style,
+    wrapMode: 'clip',
+    width: columns,
+    cursor: focus.isFocused ? { x: Graphemes.cursorColumn(graphemes.slice(scroll.index).join(''), currentCursor - scroll.index), y: 0 } : undefined
+  }, text + ' '.repeat(Math.max(0, columns - Graphemes.lineWidth(text))))
+}
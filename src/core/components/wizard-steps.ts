@@ -0,0 +1,45 @@
+import { intrinsics, Style, VNode } from 'core/view'
+
+export interface WizardStepsProps {
+  /** Step labels */
+  steps: string[]
+  /** Index of the current step */
+  current: number
+  /** Called with the index of a clicked completed step, e.g. to go back to it */
+  onClick?: (index: number) => void
+  /** Merged over the default styles, e.g. from a theme */
+  styles?: WizardStepsStyles
+}
+
+export interface WizardStepsStyles {
+  summary?: Style
+  completed?: Style
+  current?: Style
+  upcoming?: Style
+}
+
+const DEFAULT_WIZARD_STEPS_STYLES: Required<WizardStepsStyles> = {
+  summary: { color: 'gray' },
+  completed: { color: 'green' },
+  current: { color: 'white', bold: true },
+  upcoming: { color: 'gray' }
+}
+
+/**
+ * Shows "Step N of M" and each step, marked as completed (✓), current (●) or upcoming (○).
+ * The props include those of {@link PromptProgress}, so prompt wizards can pass their `progress` directly
+ */
+export function WizardSteps ({ steps, current, onClick, styles }: WizardStepsProps): VNode {
+  return intrinsics.vbox({},
+    intrinsics.text({ style: Style.merge(DEFAULT_WIZARD_STEPS_STYLES.summary, styles?.summary) }, `Step ${Math.min(current + 1, steps.length)} of ${steps.length}`),
+    intrinsics.hbox({ gap: 2 }, ...steps.map((step, index) => {
+      const isCompleted = index < current
+      const isCurrent = index === current
+      const state = isCurrent ? 'current' : isCompleted ? 'completed' : 'upcoming'
+      return intrinsics.text({
+        style: Style.merge(DEFAULT_WIZARD_STEPS_STYLES[state], styles?.[state]),
+        onClick: isCompleted && onClick !== undefined ? () => onClick(index) : undefined
+      }, `${isCompleted ? '✓' : isCurrent ? '●' : '○'} ${step}`)
+    }))
+  )
+}
@@ -0,0 +1,36 @@
+/**
+ * Sources of randomness and time to pass to the renderer (`random` and `now` in the render options),
+ * so that tests and replays produce the same output every run, even for components using random ids or timestamps.
+ * Components get them via {@link useRandom} and {@link useNow}
+ */
+export module Deterministic {
+  /** A seeded pseudo-random number generator (mulberry32), like `Math.random` but the same sequence for the same seed */
+  export function seededRandom (seed: number): () => number {
+    let state = seed >>> 0
+    return () => {
+      state = (state + 0x6d2b79f5) >>> 0
+      let result = state
+      result = Math.imul(result ^ (result >>> 15), result | 1)
+      result ^= result + Math.imul(result ^ (result >>> 7), result | 61)
+      return ((result ^ (result >>> 14)) >>> 0) / 4294967296
+    }
+  }
+
+  /** A clock which starts at `startMs` and advances by `stepMs` every time it's read */
+  export function steppingClock (startMs: number = 0, stepMs: number = 0): () => number {
+    let time = startMs - stepMs
+    return () => {
+      time += stepMs
+      return time
+    }
+  }
+
+  /** A clock which only advances when `advance` is called, e.g. to step through time in a test */
+  export function manualClock (startMs: number = 0): { now: () => number, advance: (ms: number) => void } {
+    let time = startMs
+    return {
+      now: () => time,
+      advance: ms => { time += ms }
+    }
+  }
+}
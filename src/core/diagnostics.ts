@@ -0,0 +1,37 @@
+import { getVComponentPath, getWarningSink } from 'core/component'
+
+/**
+ * Warnings about likely mistakes (e.g. text overflowing without a wrap mode).
+ * Many of these are detected every frame, so each distinct message is only sent to the sink once
+ * (see `warningSink` and `dedupeWarnings` in `setGlobalComponentOpts`)
+ */
+export module Diagnostics {
+  /** So warnings with changing details (e.g. sizes) don't grow {@link SENT_WARNINGS} forever. The oldest are forgotten first */
+  const MAX_SENT_WARNINGS = 1000
+  /** In the order they were sent */
+  const SENT_WARNINGS: Set<string> = new Set()
+
+  /**
+   * If called while a component is updating, the component's path is added to the message.
+   * Warnings are deduplicated without the path, so the same mistake in every item of a list is only sent once
+   */
+  export function warn (message: string): void {
+    const { sink, dedupe } = getWarningSink()
+    if (dedupe) {
+      if (SENT_WARNINGS.has(message)) {
+        return
+      }
+      SENT_WARNINGS.add(message)
+      if (SENT_WARNINGS.size > MAX_SENT_WARNINGS) {
+        SENT_WARNINGS.delete(SENT_WARNINGS.values().next().value)
+      }
+    }
+    const componentPath = getVComponentPath()
+    sink(componentPath === '' ? message : `${message} (in component ${componentPath})`)
+  }
+
+  /** Lets warnings which were already sent be sent again */
+  export function resetWarnings (): void {
+    SENT_WARNINGS.clear()
+  }
+}
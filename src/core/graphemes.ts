@@ -0,0 +1,69 @@
+import { Strings } from '@raycenity/misc-ts'
+
+/**
+ * User-perceived characters (grapheme clusters), e.g. an emoji ZWJ sequence, a flag, or a letter with combining marks.
+ * The terminal renderer puts each grapheme in one cell (two if it's wide), so splitting by code point breaks column math
+ */
+export module Graphemes {
+  const SEGMENTER: Intl.Segmenter | null = typeof Intl.Segmenter === 'function'
+    ? new Intl.Segmenter(undefined, { granularity: 'grapheme' })
+    : null
+
+  /** Splits into graphemes. Falls back to code points if `Intl.Segmenter` isn't available */
+  export function split (text: string): string[] {
+    if (SEGMENTER === null) {
+      return [...text]
+    }
+    return [...SEGMENTER.segment(text)].map(({ segment }) => segment)
+  }
+
+  /** Width of a single grapheme in columns: 0, 1, or 2 */
+  export function width (grapheme: string): number {
+    const codePointWidth = Strings.width(grapheme)
+    // Multi-code-point graphemes (ZWJ sequences, flags, jamo) are at most 2 columns, even though their code points sum to more
+    return Math.min(codePointWidth, 2)
+  }
+
+  /** Width of a line of text in columns */
+  export function lineWidth (line: string): number {
+    return split(line).reduce((sum, grapheme) => sum + width(grapheme), 0)
+  }
+
+  // Editing, for text inputs. Cursors are grapheme indices (0 is before the first, `count(text)` after the last),
+  // so editing never splits a grapheme, and `cursorColumn` converts them to columns for rendering
+
+  /** Number of graphemes */
+  export function count (text: string): number {
+    return split(text).length
+  }
+
+  /** Column of the cursor at grapheme `index` in a line, e.g. to position the terminal cursor */
+  export function cursorColumn (line: string, index: number): number {
+    return lineWidth(split(line).slice(0, index).join(''))
+  }
+
+  /** `text` with `inserted` before grapheme `index` */
+  export function insert (text: string, index: number, inserted: string): string {
+    const graphemes = split(text)
+    return [...graphemes.slice(0, index), inserted, ...graphemes.slice(index)].join('')
+  }
+
+  /** `text` without the grapheme before `index` (backspace). Unchanged if `index` is 0 */
+  export function removeBefore (text: string, index: number): string {
+    if (index <= 0) {
+      return text
+    }
+    const graphemes = split(text)
+    return [...graphemes.slice(0, index - 1), ...graphemes.slice(index)].join('')
+  }
+
+  /** `text` without the grapheme at `index` (delete). Unchanged if `index` is at the end */
+  export function removeAt (text: string, index: number): string {
+    return removeBefore(text, index + 1)
+  }
+
+  /** Whether `text` is a single grapheme, e.g. to tell typed characters (including emoji and CJK) from control sequences */
+  export function isSingle (text: string): boolean {
+    return text !== '' && count(text) === 1
+  }
+}
@@ -0,0 +1,28 @@
+import { VNode } from 'core/view'
+
+/**
+ * Wraps a component so that it gets extra props from `inject`, e.g. a theme or layout direction from a context.
+ * `inject` is called in the component, so it can call hooks like `useConsume`.
+ * Props passed explicitly take priority over injected ones.
+ *
+ * ```jsx
+ * const Label = withContextProps(
+ *   ({ text, color }: { text: string, color: Color }) => <text color={color}>{text}</text>,
+ *   () => ({ color: themeContext.useConsume()?.foreground ?? 'white' })
+ * )
+ * // <Label text='hello' />
+ * ```
+ */
+export function withContextProps<Props extends object, Injected extends object> (
+  component: (props: Props & Injected) => VNode,
+  inject: () => Injected
+): (props: Omit<Props, keyof Injected> & Partial<Injected>) => VNode {
+  const wrapped = (props: Omit<Props, keyof Injected> & Partial<Injected>): VNode => {
+    const injected = inject()
+    const explicit = Object.fromEntries(Object.entries(props).filter(([, value]) => value !== undefined))
+    return component({ ...injected, ...explicit } as unknown as Props & Injected)
+  }
+  // The name is the component's default key
+  Object.defineProperty(wrapped, 'name', { value: component.name })
+  return wrapped
+}
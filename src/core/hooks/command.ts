@@ -0,0 +1,97 @@
+import type { ChildProcess } from 'child_process'
+import { useEffect, useStateFast } from 'core/hooks/intrinsic'
+import { PLATFORM } from 'core/platform'
+
+export interface CommandOutputLine {
+  stream: 'stdout' | 'stderr'
+  text: string
+}
+
+export interface CommandState {
+  /** The last `maxLines` lines of output, oldest first */
+  lines: CommandOutputLine[]
+  /**
+   * - `'running'`: still running
+   * - `{ exitCode, signal }`: exited; `exitCode` is null if killed by `signal`
+   * - `{ error }`: couldn't be spawned
+   */
+  status: 'running' | { exitCode: number | null, signal: string | null } | { error: Error }
+}
+
+export interface UseCommandOptions {
+  cwd?: string
+  env?: Record<string, string | undefined>
+  /** Older lines are dropped, default 1000 */
+  maxLines?: number
+}
+
+/**
+ * Runs `command` with `args`, streaming its output into the returned state line by line.
+ * The process is killed when the component is destroyed, and restarted if `command`, `args` or `cwd` change.
+ * Only works in the terminal.
+ */
+export function useCommand (command: string, args: string[] = [], { cwd, env, maxLines = 1000 }: UseCommandOptions = {}): CommandState {
+  const [state, setState] = useStateFast<CommandState>({ lines: [], status: 'running' })
+
+  useEffect(() => {
+    if (PLATFORM !== 'cli') {
+      setState({ lines: [], status: { error: new Error('useCommand is only supported in the terminal') } })
+      return
+    }
+    if (state.lines.length > 0 || state.status !== 'running') {
+      setState({ lines: [], status: 'running' })
+    }
+
+    let isAlive = true
+    let child: ChildProcess | null = null
+    // The state is replaced (not mutated) so the component sees each change
+    let lines: CommandOutputLine[] = []
+    const partialLines = { stdout: '', stderr: '' }
+
+    const addOutput = (stream: 'stdout' | 'stderr', chunk: string): void => {
+      const newLines = (partialLines[stream] + chunk).split(/\r?\n/)
+      partialLines[stream] = newLines.pop()!
+      if (newLines.length > 0) {
+        lines = [...lines, ...newLines.map(text => ({ stream, text }))].slice(-maxLines)
+        setState({ lines, status: 'running' })
+      }
+    }
+    const flushPartialLines = (): void => {
+      for (const stream of ['stdout', 'stderr'] as const) {
+        if (partialLines[stream] !== '') {
+          lines = [...lines, { stream, text: partialLines[stream] }].slice(-maxLines)
+          partialLines[stream] = ''
+        }
+      }
+    }
+
+    void import('child_process').then(({ spawn }) => {
+      if (!isAlive) {
+        return
+      }
+      child = spawn(command, args, { cwd, env: env ?? process.env })
+      child.stdout?.setEncoding('utf8').on('data', (chunk: string) => { if (isAlive) addOutput('stdout', chunk) })
+      child.stderr?.setEncoding('utf8').on('data', (chunk: string) => { if (isAlive) addOutput('stderr', chunk) })
+      child.on('error', error => {
+        if (isAlive) {
+          setState({ lines, status: { error } })
+        }
+      })
+      child.on('close', (exitCode, signal) => {
+        if (isAlive) {
+          flushPartialLines()
+          setState({ lines, status: { exitCode, signal } })
+        }
+      })
+    })
+
+    return () => {
+      isAlive = false
+      if (child !== null && child.exitCode === null && child.signalCode === null) {
+        child.kill()
+      }
+    }
+  }, { onChange: [command, args.join('\0'), cwd] })
+
+  return state
+}
@@ -0,0 +1,27 @@
+import { createContext } from 'core/hooks/intrinsic/props-context'
+import { iterVComponentAncestorsTopDown } from 'core/component'
+
+/**
+ * Writing direction. In `'rtl'`, the only change is that horizontal boxes lay out their children in reverse order (the first child is rightmost).
+ * Nothing else is mirrored: explicit positions, anchors and borders stay as they are.
+ * Text isn't reordered (bidi) either, and most terminals don't do it themselves, so RTL text is shown in logical order
+ */
+export type WritingDirection = 'ltr' | 'rtl'
+
+const DIRECTION_CONTEXT = createContext<WritingDirection>()
+
+/** Sets the writing direction for this component and its descendants, e.g. for Arabic or Hebrew UIs */
+export function useProvideDirection (direction: WritingDirection): void {
+  DIRECTION_CONTEXT.useProvide(direction)
+}
+
+/** The nearest provided writing direction, or `'ltr'` if none */
+export function useDirection (): WritingDirection {
+  return DIRECTION_CONTEXT.useConsume() ?? 'ltr'
+}
+
+/** {@link useDirection}, but also works outside of components (returning `'ltr'`), so intrinsics can use it */
+export function getCurrentDirection (): WritingDirection {
+  const isInComponent = !iterVComponentAncestorsTopDown().next().done
+  return isInComponent ? useDirection() : 'ltr'
+}
@@ -1,5 +1,5 @@
-import { getRenderer } from 'core/component'
-import { Key } from '@raycenity/misc-ts'
+import { getRenderer, getVComponent, getVComponentPath } from 'core/component'
+import { Capabilities, InputHandler, MouseInput } from 'core/renderer'
 import { useDynamic, useEffect, UseEffectRerun, useStateFast } from 'core'
 
 /** Returns a function which will always be called with the latest props and state dependencies. */
@@ -10,6 +10,58 @@ export function useDynamicFn<Parameters extends any[], Return> (
   return (...args: Parameters): Return => getFn()(...args)
 }
 
+/**
+ * Returns a function which calls the latest `fn` while the component exists, and does nothing after it's destroyed.
+ * Use for callbacks which may be called after the component is gone (e.g. from timers, promises or other components),
+ * since updating a destroyed component's state throws
+ */
+export function useMountedCallback<Parameters extends any[]> (fn: (...args: Parameters) => void): (...args: Parameters) => void {
+  const getFn = useDynamic(fn)
+  const component = getVComponent()
+  return (...args: Parameters): void => {
+    if (!component.isDead) {
+      getFn()(...args)
+    }
+  }
+}
+
+/**
+ * Returns an id which is unique within the renderer and stays the same while the component exists, e.g. `App/Settings/Checkbox:2`.
+ * It's the component's path (see {@link getVComponentPath}) and which hook it is, so it's also the same across runs.
+ * Use to correlate views, accessibility labels and log messages with the component
+ */
+export function useId (): string {
+  const component = getVComponent()
+  // The index of the state below, which distinguishes multiple calls in the same component
+  const [id] = useStateFast(`${getVComponentPath(component)}:${component.nextStateIndex}`)
+  return id
+}
+
+/**
+ * Returns `value` from the previous time the component rendered, or undefined when it's first created.
+ * e.g. to animate from the previous value of a prop
+ */
+export function usePrevious<T> (value: T): T | undefined {
+  // Mutated without updating the component
+  const [last] = useStateFast<{ value: T | undefined }>({ value: undefined })
+  const previous = last.value
+  last.value = value
+  return previous
+}
+
+/**
+ * Returns whether `value` changed since the previous time the component rendered, compared with `compare` if provided (otherwise `===`).
+ * False when the component is first created
+ */
+export function useChanged<T> (value: T, compare?: (lhs: T, rhs: T) => boolean): boolean {
+  // Mutated without updating the component
+  const [last] = useStateFast<{ value: T, isSet: boolean }>({ value, isSet: false })
+  const changed = last.isSet && !(compare ?? ((lhs, rhs) => lhs === rhs))(last.value, value)
+  last.value = value
+  last.isSet = true
+  return changed
+}
+
 /**
  * Lazily computes a value and then updates with the returned value.
  * Subsequent calls use the returned value, so it's not recalculated.
@@ -43,8 +95,10 @@ export function useLazy <T> (lazy: T | Promise<T>, loading: T): T {
 
 /**
  * Read keyboard input inside of your component.
+ * `source` is where the input came from: the platform (`'terminal'` or `'browser'`), `'synthetic'`,
+ * or a source added via `addInputSource`.
  */
-export function useInput (handler: (key: Key) => void): void {
+export function useInput (handler: InputHandler): void {
   handler = useDynamicFn(handler)
 
   const renderer = getRenderer()
@@ -53,6 +107,41 @@ export function useInput (handler: (key: Key) => void): void {
   }, 'on-create')
 }
 
+/**
+ * Read mouse input inside of your component. Coordinates are in columns and rows.
+ * To handle clicks on a specific view, it's usually easier to use the view's `onClick`.
+ */
+export function useMouse (handler: (event: MouseInput) => void): void {
+  handler = useDynamicFn(handler)
+
+  const renderer = getRenderer()
+  useEffect(() => {
+    return renderer.useMouse(handler)
+  }, 'on-create')
+}
+
+/**
+ * Read pasted text inside of your component.
+ * In the terminal this requires bracketed paste (enabled by default), otherwise pasted text is sent to `useInput` one key at a time.
+ */
+export function usePaste (handler: (text: string) => void): void {
+  handler = useDynamicFn(handler)
+
+  const renderer = getRenderer()
+  useEffect(() => {
+    return renderer.usePaste(handler)
+  }, 'on-create')
+}
+
+/**
+ * Returns a function which copies text to the system clipboard.
+ * In the terminal this uses OSC 52, so it works over SSH if the terminal supports it.
+ */
+export function useClipboard (): (text: string) => void {
+  const renderer = getRenderer()
+  return text => renderer.writeClipboard(text)
+}
+
 /**
  * Performs an action after the specified delay.
  *
@@ -78,3 +167,161 @@ export function useInterval (millis: number, handler: () => void): void {
     return () => clearInterval(interval)
   }, 'on-create')
 }
+
+/**
+ * Returns the renderer's source of random numbers in [0, 1). Use instead of `Math.random` for output which should be reproducible,
+ * e.g. random ids, since tests and replays can make it deterministic (see {@link Deterministic})
+ */
+export function useRandom (): () => number {
+  return getRenderer().random
+}
+
+/**
+ * Returns the renderer's clock (milliseconds). Use instead of `Date.now` for timestamps which are shown,
+ * since tests and replays can make it deterministic (see {@link Deterministic})
+ */
+export function useNow (): () => number {
+  return getRenderer().now
+}
+
+/** What the renderer's platform supports (images, colors, mouse, ...), so the component can adapt */
+export function useCapabilities (): Capabilities {
+  return getRenderer().getCapabilities()
+}
+
+/**
+ * Returns a function which calls `callback` only after it stops being called for `millis` milliseconds,
+ * with the last arguments. e.g. to search after the user stops typing.
+ * Pending calls are cancelled when the component is destroyed.
+ */
+export function useDebounced<Args extends any[]> (millis: number, callback: (...args: Args) => void): (...args: Args) => void {
+  callback = useDynamicFn(callback)
+  // Mutated without updating the component
+  const [pending] = useStateFast<{ timeout: ReturnType<typeof setTimeout> | null }>({ timeout: null })
+
+  useEffect(() => () => {
+    if (pending.timeout !== null) {
+      clearTimeout(pending.timeout)
+    }
+  }, 'on-create')
+
+  return (...args: Args): void => {
+    if (pending.timeout !== null) {
+      clearTimeout(pending.timeout)
+    }
+    pending.timeout = setTimeout(() => {
+      pending.timeout = null
+      callback(...args)
+    }, millis)
+  }
+}
+
+/**
+ * Returns a function which calls `callback` at most once every `millis` milliseconds.
+ * The first call goes through immediately, calls within the next `millis` are combined into one call at the end with the last arguments.
+ * Time is measured with the renderer's clock (see {@link useNow}).
+ * Pending calls are cancelled when the component is destroyed.
+ */
+export function useThrottled<Args extends any[]> (millis: number, callback: (...args: Args) => void): (...args: Args) => void {
+  const renderer = getRenderer()
+  callback = useDynamicFn(callback)
+  // Mutated without updating the component
+  const [throttle] = useStateFast<{ lastCall: number, timeout: ReturnType<typeof setTimeout> | null, args: Args | null }>({
+    lastCall: -Infinity,
+    timeout: null,
+    args: null
+  })
+
+  useEffect(() => () => {
+    if (throttle.timeout !== null) {
+      clearTimeout(throttle.timeout)
+    }
+  }, 'on-create')
+
+  const call = (args: Args): void => {
+    throttle.lastCall = renderer.now()
+    throttle.args = null
+    callback(...args)
+  }
+  return (...args: Args): void => {
+    const sinceLastCall = renderer.now() - throttle.lastCall
+    if (sinceLastCall >= millis && throttle.timeout === null) {
+      call(args)
+    } else {
+      throttle.args = args
+      if (throttle.timeout === null) {
+        throttle.timeout = setTimeout(() => {
+          throttle.timeout = null
+          if (throttle.args !== null) {
+            call(throttle.args)
+          }
+        }, millis - sinceLastCall)
+      }
+    }
+  }
+}
+
+/**
+ * Whether there has been no key, mouse or paste input for `millis` milliseconds,
+ * e.g. to dim the screen or pause animations. Updates the component when it changes
+ */
+export function useIdle (millis: number): boolean {
+  const renderer = getRenderer()
+  const [isIdle, setIsIdle] = useStateFast(renderer.now() - renderer.getLastInputTime() >= millis)
+
+  useEffect(() => {
+    let timeout: ReturnType<typeof setTimeout> | null = null
+    const schedule = (): void => {
+      if (timeout !== null) {
+        clearTimeout(timeout)
+        timeout = null
+      }
+      const remaining = renderer.getLastInputTime() + millis - renderer.now()
+      if (remaining <= 0) {
+        setIsIdle(true)
+      } else {
+        timeout = setTimeout(schedule, remaining)
+      }
+    }
+    const onActivity = (): void => {
+      setIsIdle(false)
+      schedule()
+    }
+
+    const removeHandlers = [renderer.useInput(onActivity), renderer.useMouse(onActivity), renderer.usePaste(onActivity)]
+    schedule()
+    return () => {
+      if (timeout !== null) {
+        clearTimeout(timeout)
+      }
+      for (const removeHandler of removeHandlers) {
+        removeHandler()
+      }
+    }
+  }, { onChange: [millis] })
+
+  return isIdle
+}
+
+/**
+ * While the component exists, `blocker` is called when the UI is asked to close (`requestClose`),
+ * and the UI only closes if it returns (or resolves to) true.
+ * e.g. show a confirmation if there are unsaved changes, and resolve with the user's answer
+ */
+export function useExitBlocker (blocker: () => boolean | Promise<boolean>): void {
+  blocker = useDynamicFn(blocker)
+
+  const renderer = getRenderer()
+  useEffect(() => {
+    return renderer.addExitBlocker(blocker)
+  }, 'on-create')
+}
+
+/**
+ * Adds `context` (e.g. which item is being edited) to errors thrown while updating this component or its descendants,
+ * after the path of the component which threw. A function is only called if there's an error.
+ * Errors thrown in event handlers aren't part of an update, so they don't get it
+ */
+export function useErrorContext (context: string | (() => string)): void {
+  getVComponent().errorContexts.push(typeof context === 'function' ? context : () => context)
+}
@@ -0,0 +1,62 @@
+import { useDynamic, useEffect, useStateFast } from 'core/hooks/intrinsic'
+
+export type FetchState<T> =
+  { type: 'loading' } |
+  { type: 'ok', value: T } |
+  { type: 'error', error: Error }
+
+export interface UseFetchOptions<T> {
+  /** Passed to `fetch` */
+  init?: RequestInit
+  /** Converts the response to the value, default parses JSON */
+  parse?: (response: Response) => Promise<T>
+}
+
+/**
+ * Fetches `url` and returns the state of the request, updating the component when it finishes.
+ * Refetches when `url` changes. Responses which aren't ok (e.g. 404) are errors.
+ *
+ * ```jsx
+ * const Weather = ({ city }: { city: string }) => {
+ *   const weather = useFetch<{ temperature: number }>(`https://example.com/weather/${city}`)
+ *   switch (weather.type) {
+ *     case 'loading': return <text color='gray'>Loading...</text>
+ *     case 'ok': return <text>{weather.value.temperature}°</text>
+ *     case 'error': return <text color='red'>{weather.error.message}</text>
+ *   }
+ * }
+ * ```
+ */
+export function useFetch<T> (url: string, { init, parse }: UseFetchOptions<T> = {}): FetchState<T> {
+  const [state, setState] = useStateFast<FetchState<T>>({ type: 'loading' })
+  const getUrl = useDynamic(url)
+
+  useEffect(() => {
+    let isAlive = true
+    if (state.type !== 'loading') {
+      setState({ type: 'loading' })
+    }
+    // Ignore the response if the url changed or the component was destroyed
+    const setStateIfCurrent = (newState: FetchState<T>): void => {
+      if (isAlive && getUrl() === url) {
+        setState(newState)
+      }
+    }
+
+    fetch(url, init).then(async response => {
+      if (!response.ok) {
+        throw new Error(`${response.status} ${response.statusText}: ${url}`)
+      }
+      return await (parse ?? (async response => await response.json()))(response)
+    }).then(
+      value => setStateIfCurrent({ type: 'ok', value }),
+      error => setStateIfCurrent({ type: 'error', error: error instanceof Error ? error : new Error(String(error)) })
+    )
+
+    return () => {
+      isAlive = false
+    }
+  }, { onChange: [url] })
+
+  return state
+}
@@ -0,0 +1,61 @@
+import type { FSWatcher, Stats } from 'fs'
+import { useEffect, useStateFast } from 'core/hooks/intrinsic'
+import { PLATFORM } from 'core/platform'
+
+export type FileWatchState =
+  { type: 'loading' } |
+  { type: 'ok', contents: string, stats: Stats } |
+  { type: 'error', error: Error }
+
+/**
+ * Reads the file at `path` and updates the component with its new contents whenever it changes,
+ * e.g. to tail a log or reload a config. Only works in the terminal.
+ */
+export function useFileWatch (path: string, encoding: BufferEncoding = 'utf8'): FileWatchState {
+  const [state, setState] = useStateFast<FileWatchState>({ type: 'loading' })
+
+  useEffect(() => {
+    if (PLATFORM !== 'cli') {
+      setState({ type: 'error', error: new Error('useFileWatch is only supported in the terminal') })
+      return
+    }
+
+    let isAlive = true
+    let watcher: FSWatcher | null = null
+    // Changes can come faster than reads finish, so only the latest read is used
+    let lastRead = 0
+    const read = async (fs: typeof import('fs')): Promise<void> => {
+      const thisRead = ++lastRead
+      let newState: FileWatchState
+      try {
+        const [contents, stats] = await Promise.all([fs.promises.readFile(path, encoding), fs.promises.stat(path)])
+        newState = { type: 'ok', contents, stats }
+      } catch (error) {
+        newState = { type: 'error', error: error as Error }
+      }
+      if (isAlive && thisRead === lastRead) {
+        setState(newState)
+      }
+    }
+
+    void import('fs').then(fs => {
+      if (!isAlive) {
+        return
+      }
+      void read(fs)
+      watcher = fs.watch(path, () => { void read(fs) })
+      watcher.on('error', error => {
+        if (isAlive) {
+          setState({ type: 'error', error })
+        }
+      })
+    })
+
+    return () => {
+      isAlive = false
+      watcher?.close()
+    }
+  }, { onChange: [path, encoding] })
+
+  return state
+}
@@ -0,0 +1,425 @@
+import { getRenderer, getVComponentPath } from 'core/component'
+import { createContext } from 'core/hooks/intrinsic/props-context'
+import { useDynamic, useEffect, useState, useStateFast } from 'core/hooks/intrinsic'
+import { useDynamicFn, useId } from 'core/hooks/extra'
+import { Lens } from 'core/lens'
+import { RendererImpl } from 'renderer/common'
+
+export interface FocusHandle {
+  /** Identifies this focusable in {@link FocusChangeEvent}s, see {@link useId} */
+  readonly id: string
+  /** Whether this has keyboard focus. The component updates when it changes */
+  readonly isFocused: boolean
+  /** Focuses this, unless it's disabled or outside of the trapping scope (see {@link useFocusScope}) which has focus */
+  requestFocus: () => void
+  /** Unfocuses this if it's focused, so nothing is focused */
+  blur: () => void
+}
+
+export interface FocusScopeHandle {
+  /** Focuses the next focusable in this scope, wrapping around */
+  focusNext: () => void
+  /** Focuses the previous focusable in this scope, wrapping around */
+  focusPrevious: () => void
+  /** Unfocuses whatever is focused if it's in this scope */
+  blur: () => void
+}
+
+/**
+ * Why focus changed: `'request'` from {@link FocusHandle.requestFocus} or a scope's `focusNext` / `focusPrevious`,
+ * `'tab'` from Tab or Shift-Tab, `'blur'` from `blur` or the focusable being disabled, `'trap'` from a trapping scope being created,
+ * and `'restore'` after the focused focusable was removed or the trapping scope it was in closed
+ */
+export type FocusChangeReason = 'request' | 'tab' | 'blur' | 'trap' | 'restore'
+
+export interface FocusChangeEvent {
+  reason: FocusChangeReason
+  /** The {@link FocusHandle.id} of what's focused now, or null if nothing is */
+  focusedId: string | null
+}
+
+export interface UseFocusOptions {
+  /** Disabled focusables are skipped by Tab and can't be focused. Default is true */
+  enabled?: boolean
+  /** Focus when created. Default is false */
+  autoFocus?: boolean
+  /** Typed characters go to this while it's focused, e.g. a text input, so {@link useKeymap} ignores printable keys. Default is false */
+  editsText?: boolean
+}
+
+export interface UseFocusScopeOptions {
+  /** While the scope exists, Tab only moves between focusables in it, and focus moves into it. e.g. for a modal. Default is false */
+  trap?: boolean
+}
+
+interface FocusScope {
+  readonly parent: FocusScope | null
+}
+
+interface Focusable {
+  readonly id: string
+  /** Of the component which called {@link useFocus}, see {@link getVComponentPath} */
+  readonly path: string
+  readonly scope: FocusScope
+  enabled: boolean
+  editsText: boolean
+  /** The innermost scroll container, see {@link useScrollContainer} */
+  container: ScrollContainer | null
+  readonly focused: Lens<boolean>
+}
+
+interface ScrollContainer {
+  /** Scrolls the descendant into view, in this and outer containers */
+  scrollIntoView: () => void
+  outer: ScrollContainer | null
+  position: ScrollContainerPosition | null
+}
+
+/** Which item of a scrolling view a container is, see {@link useScrollContainer} */
+export interface ScrollContainerPosition {
+  /** Identifies the scrolling view, e.g. an object in its state */
+  group: object
+  index: number
+}
+
+interface FocusState {
+  readonly root: FocusScope
+  /** In the order they were created, which is the Tab order */
+  readonly focusables: Focusable[]
+  /** Trapping scopes which exist, latest last. Focus stays in the latest */
+  readonly traps: FocusTrap[]
+  current: Focusable | null
+  /** Set when the focused focusable is removed, until focus is restored, see {@link scheduleRestore} */
+  pendingRestore: { index: number, returnTo: Focusable | null } | null
+  readonly listeners: Set<(event: FocusChangeEvent) => void>
+  /** Set while there are focusables, see {@link addFocusable} */
+  removeTabHandler: (() => void) | null
+}
+
+interface FocusTrap {
+  readonly scope: FocusScope
+  /** What was focused when the trap was created, which is focused again when it closes */
+  readonly returnTo: Focusable | null
+}
+
+const FOCUS_STATES: WeakMap<RendererImpl<any, any>, FocusState> = new WeakMap()
+
+const FOCUS_SCOPE_CONTEXT = createContext<FocusScope>()
+
+const SCROLL_CONTAINER_CONTEXT = createContext<ScrollContainer>()
+
+function getFocusState (renderer: RendererImpl<any, any>): FocusState {
+  let state = FOCUS_STATES.get(renderer)
+  if (state === undefined) {
+    state = { root: { parent: null }, focusables: [], traps: [], current: null, pendingRestore: null, listeners: new Set(), removeTabHandler: null }
+    FOCUS_STATES.set(renderer, state)
+  }
+  return state
+}
+
+/** The index of the item of `group` which `focusable` is in, or null if it's not in the group */
+function indexInGroup (focusable: Focusable, group: object): number | null {
+  for (let container = focusable.container; container !== null; container = container.outer) {
+    if (container.position?.group === group) {
+      return container.position.index
+    }
+  }
+  return null
+}
+
+/**
+ * Where `focusable` goes in the Tab order. Usually last, since components are created in order,
+ * but items of a scrolling view are created as they scroll into view, so they go among the other items by index
+ */
+function tabOrderIndex (state: FocusState, focusable: Focusable): number {
+  let container = focusable.container
+  while (container !== null && container.position === null) {
+    container = container.outer
+  }
+  if (container === null) {
+    return state.focusables.length
+  }
+  const { group, index } = container.position!
+  let after = -1
+  let before = -1
+  state.focusables.forEach((other, i) => {
+    const otherIndex = indexInGroup(other, group)
+    if (otherIndex !== null && otherIndex <= index) {
+      after = i
+    } else if (otherIndex !== null && before === -1) {
+      before = i
+    }
+  })
+  return after !== -1 ? after + 1 : before !== -1 ? before : state.focusables.length
+}
+
+/** Adds `focusable` to the Tab order, and listens for Tab if it's the first */
+function addFocusable (renderer: RendererImpl<any, any>, state: FocusState, focusable: Focusable): void {
+  state.focusables.splice(tabOrderIndex(state, focusable), 0, focusable)
+  if (state.removeTabHandler === null) {
+    state.removeTabHandler = renderer.useInput(key => {
+      if (key.name === 'tab' && key.ctrl !== true && key.meta !== true) {
+        move(state, activeScope(state), key.shift === true ? -1 : 1, 'tab')
+      }
+    })
+  }
+}
+
+/** Removes `focusable` from the Tab order, and stops listening for Tab if it was the last */
+function removeFocusable (state: FocusState, focusable: Focusable): void {
+  const index = state.focusables.indexOf(focusable)
+  state.focusables.splice(index, 1)
+  if (state.current === focusable) {
+    // Not focus(state, null), because the component is gone
+    state.current = null
+    scheduleRestore(state, index, null)
+  }
+  if (state.focusables.length === 0 && state.removeTabHandler !== null) {
+    state.removeTabHandler()
+    state.removeTabHandler = null
+  }
+}
+
+function isInScope (focusable: Focusable, scope: FocusScope): boolean {
+  for (let ancestor: FocusScope | null = focusable.scope; ancestor !== null; ancestor = ancestor.parent) {
+    if (ancestor === scope) {
+      return true
+    }
+  }
+  return false
+}
+
+function activeScope (state: FocusState): FocusScope {
+  return state.traps[state.traps.length - 1]?.scope ?? state.root
+}
+
+function canFocus (state: FocusState, focusable: Focusable, scope: FocusScope = activeScope(state)): boolean {
+  return focusable.enabled && isInScope(focusable, scope)
+}
+
+function focus (state: FocusState, focusable: Focusable | null, reason: FocusChangeReason): void {
+  if (state.current === focusable) {
+    return
+  }
+  const previous = state.current
+  state.current = focusable
+  if (previous !== null) {
+    previous.focused.v = false
+  }
+  if (focusable !== null) {
+    focusable.focused.v = true
+    focusable.container?.scrollIntoView()
+  }
+  notify(state, reason)
+}
+
+function notify (state: FocusState, reason: FocusChangeReason): void {
+  const event: FocusChangeEvent = { reason, focusedId: state.current?.id ?? null }
+  for (const listener of [...state.listeners]) {
+    listener(event)
+  }
+}
+
+/**
+ * Restores focus on the next microtask, since it's usually removed because its component is being destroyed,
+ * and its neighbors may be destroyed too. Focuses `returnTo` if it's still there and focusable,
+ * otherwise the nearest survivor: the first focusable at or after `index` in the Tab order, or the last before it
+ */
+function scheduleRestore (state: FocusState, index: number, returnTo: Focusable | null): void {
+  if (state.pendingRestore !== null) {
+    state.pendingRestore = { index: Math.min(index, state.pendingRestore.index), returnTo: state.pendingRestore.returnTo ?? returnTo }
+    return
+  }
+  state.pendingRestore = { index, returnTo }
+  queueMicrotask(() => {
+    const pending = state.pendingRestore
+    state.pendingRestore = null
+    // Something else may have been focused in the meantime
+    if (pending === null || state.current !== null) {
+      return
+    }
+    const { index, returnTo } = pending
+    const next = returnTo !== null && state.focusables.includes(returnTo) && canFocus(state, returnTo)
+      ? returnTo
+      : state.focusables.slice(index).find(focusable => canFocus(state, focusable)) ??
+        state.focusables.slice(0, index).reverse().find(focusable => canFocus(state, focusable)) ??
+        null
+    if (next === null) {
+      // Still let observers know that focus was lost
+      notify(state, 'restore')
+    } else {
+      focus(state, next, 'restore')
+    }
+  })
+}
+
+/** Focuses the focusable `offset` after the current one in `scope`, wrapping around, or the first (or last) if none in `scope` is focused */
+function move (state: FocusState, scope: FocusScope, offset: number, reason: FocusChangeReason): void {
+  const candidates = state.focusables.filter(focusable => canFocus(state, focusable, scope))
+  if (candidates.length === 0) {
+    return
+  }
+  const index = state.current === null ? -1 : candidates.indexOf(state.current)
+  const next = index === -1
+    ? (offset > 0 ? 0 : candidates.length - 1)
+    : (index + offset + candidates.length) % candidates.length
+  focus(state, candidates[next], reason)
+}
+
+/**
+ * Makes something keyboard-focusable. Tab and Shift-Tab move focus between focusables in the order they were created,
+ * within the innermost trapping {@link useFocusScope}. If the focused focusable is removed, its nearest neighbor is focused.
+ * Call once per focusable, which may be multiple times in a component:
+ *
+ * ```jsx
+ * const newsletterFocus = useFocus({ autoFocus: true })
+ * const agreeFocus = useFocus()
+ * return <vbox>
+ *   <Checkbox label='Newsletter' focused={newsletterFocus.isFocused} ... />
+ *   <Checkbox label='I agree' focused={agreeFocus.isFocused} ... />
+ * </vbox>
+ * ```
+ *
+ * Widgets like {@link Button} and {@link TextInput} call `useFocus` themselves:
+ *
+ * ```jsx
+ * <TextInput value={name.v} onChange={value => { name.v = value }} autoFocus />
+ * ```
+ */
+export function useFocus ({ enabled, autoFocus, editsText }: UseFocusOptions = {}): FocusHandle {
+  const renderer = getRenderer()
+  const state = getFocusState(renderer)
+  const scope = FOCUS_SCOPE_CONTEXT.useConsume() ?? state.root
+  const id = useId()
+  const focused = useState(false)
+  const container = SCROLL_CONTAINER_CONTEXT.useConsume()
+  const [focusable] = useStateFast<Focusable>({ id, path: getVComponentPath(), scope, enabled: enabled ?? true, editsText: editsText ?? false, container, focused })
+  focusable.enabled = enabled ?? true
+  focusable.editsText = editsText ?? false
+  focusable.container = container
+
+  useEffect(() => {
+    addFocusable(renderer, state, focusable)
+    if (autoFocus === true && canFocus(state, focusable)) {
+      focus(state, focusable, 'request')
+    }
+    return () => removeFocusable(state, focusable)
+  }, 'on-create')
+  useEffect(() => {
+    if (!focusable.enabled && state.current === focusable) {
+      focus(state, null, 'blur')
+    }
+  }, { onChange: [focusable.enabled] })
+
+  return {
+    id,
+    isFocused: focused.v,
+    requestFocus: () => {
+      if (canFocus(state, focusable)) {
+        focus(state, focusable, 'request')
+      }
+    },
+    blur: () => {
+      if (state.current === focusable) {
+        focus(state, null, 'blur')
+      }
+    }
+  }
+}
+
+/**
+ * Groups the focusables of descendant components, so they can be moved between with the returned handle, e.g. from an effect.
+ * If `trap` is set, Tab stays within the scope while it exists, like in a modal, and what was focused before is focused again when it closes
+ */
+export function useFocusScope ({ trap }: UseFocusScopeOptions = {}): FocusScopeHandle {
+  const state = getFocusState(getRenderer())
+  const parent = FOCUS_SCOPE_CONTEXT.useConsume() ?? state.root
+  const [scope] = useStateFast<FocusScope>({ parent })
+  FOCUS_SCOPE_CONTEXT.useProvide(scope)
+  const isTrapping = useDynamic(trap === true)
+
+  useEffect(() => {
+    // Also when `trap` changes, so a scope can start or stop trapping, e.g. when a panel becomes modal
+    if (trap !== true) {
+      return
+    }
+    const focusTrap: FocusTrap = { scope, returnTo: state.current }
+    state.traps.push(focusTrap)
+    // Descendants are created first, so their focusables are registered
+    if (state.current === null || !isInScope(state.current, scope)) {
+      move(state, scope, 1, 'trap')
+    }
+    return () => {
+      state.traps.splice(state.traps.lastIndexOf(focusTrap), 1)
+      // If the scope just stopped trapping, it's still there, so focus stays where it is
+      if (isTrapping() && (state.current === null || isInScope(state.current, scope))) {
+        // Not focus(state, null), because the focusable may be gone too
+        state.current = null
+        scheduleRestore(state, 0, focusTrap.returnTo)
+      }
+    }
+  }, { onChange: [trap] })
+
+  return {
+    focusNext: () => move(state, scope, 1, 'request'),
+    focusPrevious: () => move(state, scope, -1, 'request'),
+    blur: () => {
+      if (state.current !== null && isInScope(state.current, scope)) {
+        focus(state, null, 'blur')
+      }
+    }
+  }
+}
+
+/**
+ * Makes focusables in descendant components call `scrollIntoView` when they're focused, so they're visible.
+ * Use in a component containing one item of a scrolling view. Outer containers are scrolled too, after this one.
+ * If `position` is given, the item's focusables are put among the other items' in the Tab order by index,
+ * even though items are created as they scroll into view, and {@link getFocusedIndex} finds the item with focus
+ */
+export function useScrollContainer (scrollIntoView: () => void, position?: ScrollContainerPosition): void {
+  const outer = SCROLL_CONTAINER_CONTEXT.useConsume()
+  // The same object every update, so descendants aren't updated when it changes
+  const [container] = useStateFast<ScrollContainer>({ scrollIntoView, outer, position: position ?? null })
+  container.scrollIntoView = () => {
+    scrollIntoView()
+    outer?.scrollIntoView()
+  }
+  container.outer = outer
+  container.position = position ?? null
+  SCROLL_CONTAINER_CONTEXT.useProvide(container)
+}
+
+/** The index of the item of `group` (see {@link useScrollContainer}) which has focus, or null if focus isn't in the group */
+export function getFocusedIndex (group: object, renderer: RendererImpl<any, any> = getRenderer()): number | null {
+  const current = FOCUS_STATES.get(renderer)?.current ?? null
+  return current === null ? null : indexInGroup(current, group)
+}
+
+/** The {@link FocusHandle.id} of what's focused in the renderer, or null if nothing is */
+export function getFocusedId (renderer: RendererImpl<any, any> = getRenderer()): string | null {
+  return FOCUS_STATES.get(renderer)?.current?.id ?? null
+}
+
+/** The path (see {@link getVComponentPath}) of the component with what's focused in the renderer, or null if nothing is */
+export function getFocusedPath (renderer: RendererImpl<any, any> = getRenderer()): string | null {
+  return FOCUS_STATES.get(renderer)?.current?.path ?? null
+}
+
+/** Whether what's focused in the renderer takes typed characters, see {@link UseFocusOptions.editsText} */
+export function isEditingText (renderer: RendererImpl<any, any> = getRenderer()): boolean {
+  return FOCUS_STATES.get(renderer)?.current?.editsText ?? false
+}
+
+/** Calls `handler` whenever keyboard focus changes, including when it's restored after the focused focusable is removed */
+export function useFocusChange (handler: (event: FocusChangeEvent) => void): void {
+  handler = useDynamicFn(handler)
+
+  const state = getFocusState(getRenderer())
+  useEffect(() => {
+    state.listeners.add(handler)
+    return () => {
+      state.listeners.delete(handler)
+    }
+  }, 'on-create')
+}
@@ -0,0 +1,100 @@
+import { Key } from '@raycenity/misc-ts'
+import { PLATFORM } from 'core/platform'
+import { useEffect } from 'core/hooks/intrinsic'
+import { useDynamicFn } from 'core/hooks/extra'
+
+export interface GamepadButtonEvent {
+  /** Index of the gamepad in `navigator.getGamepads()` */
+  gamepad: number
+  /** Index of the button in the [standard mapping](https://w3c.github.io/gamepad/#remapping) */
+  button: number
+  pressed: boolean
+}
+
+export type GamepadButtonHandler = (event: GamepadButtonEvent) => void
+
+/** Standard gamepad buttons mapped to `KeyboardEvent.key`s: d-pad to arrow keys, A to enter, B to escape */
+export const DEFAULT_GAMEPAD_KEYS: Record<number, string> = {
+  0: 'Enter',
+  1: 'Escape',
+  12: 'ArrowUp',
+  13: 'ArrowDown',
+  14: 'ArrowLeft',
+  15: 'ArrowRight'
+}
+
+const GAMEPAD_HANDLERS: Set<GamepadButtonHandler> = new Set()
+let PREV_PRESSED: boolean[][] = []
+let IS_POLLING: boolean = false
+
+// The gamepad API has no button events, so we poll every frame while anyone is listening
+function poll (): void {
+  if (GAMEPAD_HANDLERS.size === 0) {
+    IS_POLLING = false
+    PREV_PRESSED = []
+    return
+  }
+
+  for (const gamepad of navigator.getGamepads()) {
+    if (gamepad === null) {
+      continue
+    }
+    const prevPressed = PREV_PRESSED[gamepad.index] ?? []
+    gamepad.buttons.forEach((button, index) => {
+      if (button.pressed !== (prevPressed[index] ?? false)) {
+        for (const handler of [...GAMEPAD_HANDLERS]) {
+          handler({ gamepad: gamepad.index, button: index, pressed: button.pressed })
+        }
+      }
+    })
+    PREV_PRESSED[gamepad.index] = gamepad.buttons.map(button => button.pressed)
+  }
+  requestAnimationFrame(poll)
+}
+
+/**
+ * Calls `handler` whenever a gamepad button is pressed or released. Returns a function which removes the handler.
+ *
+ * Gamepads are only supported in the browser: in the terminal this does nothing.
+ */
+export function onGamepadButton (handler: GamepadButtonHandler): () => void {
+  if (PLATFORM !== 'web') {
+    return () => {}
+  }
+
+  GAMEPAD_HANDLERS.add(handler)
+  if (!IS_POLLING) {
+    IS_POLLING = true
+    requestAnimationFrame(poll)
+  }
+  return () => {
+    GAMEPAD_HANDLERS.delete(handler)
+  }
+}
+
+/**
+ * Read gamepad input inside of your component.
+ *
+ * Gamepads are only supported in the browser: in the terminal the handler is never called.
+ */
+export function useGamepad (handler: GamepadButtonHandler): void {
+  handler = useDynamicFn(handler)
+
+  useEffect(() => {
+    return onGamepadButton(handler)
+  }, 'on-create')
+}
+
+/**
+ * An input source which sends gamepad button presses as keys, so that regular `useInput` handlers work with gamepads.
+ *
+ * Usage: `ui.addInputSource('gamepad', gamepadKeys())`
+ */
+export function gamepadKeys (mapping: Record<number, string> = DEFAULT_GAMEPAD_KEYS): (send: (key: Key) => void) => () => void {
+  return send => onGamepadButton(({ button, pressed }) => {
+    const key = mapping[button]
+    if (pressed && key !== undefined) {
+      send(Key.fromKeyboardEvent(new KeyboardEvent('keypress', { key })))
+    }
+  })
+}
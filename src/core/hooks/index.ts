@@ -1,2 +1,13 @@
 export * from 'core/hooks/intrinsic'
 export * from 'core/hooks/extra'
+export * from 'core/hooks/gamepad'
+export * from 'core/hooks/direction'
+export * from 'core/hooks/locale'
+export * from 'core/hooks/fetch'
+export * from 'core/hooks/file-watch'
+export * from 'core/hooks/command'
+export * from 'core/hooks/timer'
+export * from 'core/hooks/router'
+export * from 'core/hooks/state-machine'
+export * from 'core/hooks/keymap'
+export * from 'core/hooks/focus'
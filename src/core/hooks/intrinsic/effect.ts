@@ -41,7 +41,8 @@ export function useEffect (effect: () => void | (() => void), rerun: UseEffectRe
       })
     }
   } else if ('onChange' in rerun) {
-    const isCreated = VComponent.isBeingCreated(component)
+    // Also when the hook was added by a hot reload, so it runs once like on create
+    const isCreated = VComponent.isCreatingState(component, component.nextStateIndex)
     const ourMemo = rerun.onChange
     const compare = rerun.compare ?? ((lhs: any, rhs: any) => lhs === rhs)
     const [getMemo, setMemo] = _useDynamicState(ourMemo, false)
@@ -71,6 +72,8 @@ export function useEffect (effect: () => void | (() => void), rerun: UseEffectRe
         const oldDestructor = getDestructor()
         if (oldDestructor !== null) {
           component.permanentDestructors.splice(component.permanentDestructors.indexOf(oldDestructor), 1)
+          setDestructor(null)
+          oldDestructor()
         }
         const destructor = effect()
         if (typeof destructor === 'function') {
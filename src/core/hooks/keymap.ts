@@ -0,0 +1,184 @@
+import { Key } from '@raycenity/misc-ts'
+import { getRenderer, getVComponentPath } from 'core/component'
+import { useDynamic, useEffect, useStateFast } from 'core/hooks/intrinsic'
+import { isEditingText } from 'core/hooks/focus'
+import { Graphemes } from 'core/graphemes'
+import { RendererImpl } from 'renderer/common'
+
+/**
+ * Keys separated by spaces, pressed one after another, e.g. `'q'`, `'C-s'`, `'g g'`, `'C-x C-s'`.
+ * Each key is a name (as in {@link Key.name}, e.g. `'a'`, `'up'`, `'return'`), prefixed by any of
+ * `C-` (ctrl), `M-` (meta / alt) and `S-` (shift). A single uppercase letter is the same as `S-` and the lowercase letter
+ */
+export type KeySequence = string
+
+export interface KeyBinding {
+  action: () => void
+  /** Shown in help, e.g. `'Save'` */
+  description?: string
+}
+
+export type Keymap = Record<KeySequence, (() => void) | KeyBinding>
+
+/** A binding with a description in a keymap which is in use, see {@link getActiveBindings} */
+export interface ActiveBinding {
+  sequence: KeySequence
+  description: string
+  /** Of the component which called {@link useKeymap}, see {@link getVComponentPath} */
+  path: string
+}
+
+interface ActiveKeymap {
+  path: string
+  getKeymap: () => Keymap
+}
+
+const ACTIVE_KEYMAPS: WeakMap<RendererImpl<any, any>, Set<ActiveKeymap>> = new WeakMap()
+
+/** Bindings with descriptions in the renderer's current keymaps (see {@link useKeymap}), in the order the keymaps were created, e.g. for help */
+export function getActiveBindings (renderer: RendererImpl<any, any> = getRenderer()): ActiveBinding[] {
+  return [...(ACTIVE_KEYMAPS.get(renderer) ?? [])].flatMap(({ path, getKeymap }) =>
+    Object.entries(getKeymap()).flatMap(([sequence, binding]) =>
+      typeof binding === 'function' || binding.description === undefined ? [] : [{ sequence, description: binding.description, path }]
+    )
+  )
+}
+
+interface KeyPattern {
+  name: string
+  ctrl: boolean
+  meta: boolean
+  shift: boolean
+}
+
+export module KeySequence {
+  export function parse (sequence: KeySequence): KeyPattern[] {
+    return sequence.trim().split(/\s+/).map(parseKey)
+  }
+
+  function parseKey (key: string): KeyPattern {
+    const pattern: KeyPattern = { name: key, ctrl: false, meta: false, shift: false }
+    while (/^[CMS]-./.test(pattern.name)) {
+      switch (pattern.name[0]) {
+        case 'C':
+          pattern.ctrl = true
+          break
+        case 'M':
+          pattern.meta = true
+          break
+        case 'S':
+          pattern.shift = true
+          break
+      }
+      pattern.name = pattern.name.slice(2)
+    }
+    if (/^[A-Z]$/.test(pattern.name)) {
+      pattern.name = pattern.name.toLowerCase()
+      pattern.shift = true
+    }
+    return pattern
+  }
+
+  export function matches (pattern: KeyPattern, key: Key): boolean {
+    return key.name === pattern.name &&
+      (key.ctrl === true) === pattern.ctrl &&
+      (key.meta === true) === pattern.meta &&
+      (key.shift === true) === pattern.shift
+  }
+}
+
+/** Whether the key types a character, which a text input would insert */
+function isPrintable (key: Key): boolean {
+  return key.ctrl !== true && key.meta !== true && key.sequence !== undefined && Graphemes.isSingle(key.sequence) && key.sequence >= ' '
+}
+
+/**
+ * Calls the action bound to each key sequence when it's pressed. Sequences of multiple keys (chords, e.g. `'g g'`)
+ * must be pressed within the renderer's `chordTimeout` of each other.
+ * If one sequence is a prefix of another (e.g. `'g'` and `'g g'`), the shorter one runs when the timeout passes
+ * or a key which doesn't continue the longer one is pressed, like in Vim.
+ * Bindings with a `description` are listed by {@link getActiveBindings}, so they show in {@link HelpOverlay}.
+ * Printable keys (e.g. `'?'`, but not `'C-s'`) are ignored while a text input is focused, since they're typed into it (see {@link isEditingText}).
+ *
+ * ```ts
+ * useKeymap({
+ *   'C-s': { action: save, description: 'Save' },
+ *   'g g': () => scrollTo(0),
+ *   G: () => scrollTo(Infinity)
+ * })
+ * ```
+ */
+export function useKeymap (keymap: Keymap): void {
+  const renderer = getRenderer()
+  const path = getVComponentPath()
+  // Mutated without updating the component
+  const [chord] = useStateFast<{ keys: Key[], timeout: ReturnType<typeof setTimeout> | null }>({ keys: [], timeout: null })
+  const getKeymap = useDynamic(keymap)
+  const getBindings = (): Array<{ patterns: KeyPattern[], action: () => void }> =>
+    Object.entries(getKeymap()).map(([sequence, binding]) => ({
+      patterns: KeySequence.parse(sequence),
+      action: typeof binding === 'function' ? binding : binding.action
+    }))
+
+  useEffect(() => {
+    const activeKeymap: ActiveKeymap = { path, getKeymap }
+    const activeKeymaps = ACTIVE_KEYMAPS.get(renderer) ?? new Set()
+    ACTIVE_KEYMAPS.set(renderer, activeKeymaps)
+    activeKeymaps.add(activeKeymap)
+
+    const clearTimer = (): void => {
+      if (chord.timeout !== null) {
+        clearTimeout(chord.timeout)
+        chord.timeout = null
+      }
+    }
+    const findExact = (keys: Key[]): (() => void) | null =>
+      getBindings().find(({ patterns }) =>
+        patterns.length === keys.length && patterns.every((pattern, i) => KeySequence.matches(pattern, keys[i]))
+      )?.action ?? null
+    const hasLonger = (keys: Key[]): boolean =>
+      getBindings().some(({ patterns }) =>
+        patterns.length > keys.length && keys.every((key, i) => KeySequence.matches(patterns[i], key))
+      )
+    /** Runs the binding for the keys pressed so far, if any, and forgets them */
+    const finishChord = (): void => {
+      clearTimer()
+      const action = findExact(chord.keys)
+      chord.keys = []
+      action?.()
+    }
+
+    const removeHandler = renderer.useInput(key => {
+      if (isEditingText(renderer) && isPrintable(key)) {
+        return
+      }
+      clearTimer()
+      const keys = [...chord.keys, key]
+      if (hasLonger(keys)) {
+        chord.keys = keys
+        chord.timeout = setTimeout(finishChord, renderer.chordTimeout)
+        return
+      }
+      const action = findExact(keys)
+      if (action !== null) {
+        chord.keys = []
+        action()
+      } else if (chord.keys.length > 0) {
+        // This key doesn't continue the chord, so finish it and start over from this key
+        finishChord()
+        if (hasLonger([key])) {
+          chord.keys = [key]
+          chord.timeout = setTimeout(finishChord, renderer.chordTimeout)
+        } else {
+          findExact([key])?.()
+        }
+      }
+    })
+    return () => {
+      clearTimer()
+      chord.keys = []
+      removeHandler()
+      activeKeymaps.delete(activeKeymap)
+    }
+  }, 'on-create')
+}
@@ -0,0 +1,61 @@
+import { createContext } from 'core/hooks/intrinsic/props-context'
+
+export interface Locale {
+  /** BCP 47 language tag, e.g. `'en-US'` or `'de'`. Used for number and date formatting */
+  tag: string
+  /**
+   * Translated messages by id. Messages can reference arguments as `{name}`,
+   * e.g. `{ 'items-selected': '{count} Elemente ausgewählt' }`
+   */
+  messages?: Record<string, string>
+}
+
+export interface LocaleFormat {
+  locale: Locale
+  number: (value: number, options?: Intl.NumberFormatOptions) => string
+  date: (value: Date | number, options?: Intl.DateTimeFormatOptions) => string
+  /**
+   * Looks up the message with `id` and substitutes `args`.
+   * If the locale has no such message, uses `fallback` (or the id itself)
+   */
+  message: (id: string, args?: Record<string, string | number>, fallback?: string) => string
+}
+
+/** Used if no locale is provided: the platform's default */
+export const DEFAULT_LOCALE: Locale = {
+  tag: Intl.DateTimeFormat().resolvedOptions().locale
+}
+
+const LOCALE_CONTEXT = createContext<Locale>()
+
+/** Sets the locale for this component and its descendants */
+export function useProvideLocale (locale: Locale): void {
+  LOCALE_CONTEXT.useProvide(locale)
+}
+
+/** The nearest provided locale, or {@link DEFAULT_LOCALE} */
+export function useLocale (): Locale {
+  return LOCALE_CONTEXT.useConsume() ?? DEFAULT_LOCALE
+}
+
+/** Formatting functions for the nearest provided locale */
+export function useFormat (): LocaleFormat {
+  return LocaleFormat(useLocale())
+}
+
+export function LocaleFormat (locale: Locale): LocaleFormat {
+  return {
+    locale,
+    number: (value, options) => new Intl.NumberFormat(locale.tag, options).format(value),
+    date: (value, options) => new Intl.DateTimeFormat(locale.tag, options).format(value),
+    message: (id, args, fallback) => {
+      const template = locale.messages?.[id] ?? fallback ?? id
+      return template.replace(/\{(\w+)\}/g, (match: string, name: string) => {
+        const arg = args?.[name]
+        return arg === undefined
+          ? match
+          : typeof arg === 'number' ? new Intl.NumberFormat(locale.tag).format(arg) : arg
+      })
+    }
+  }
+}
@@ -0,0 +1,141 @@
+import { createStateContext } from 'core/hooks/intrinsic/state-context'
+import { useInput } from 'core/hooks/extra'
+import { VNode } from 'core/view'
+
+export interface RouterHistory<Route> {
+  /** Every route visited, including ones gone back from */
+  entries: Route[]
+  /** Index of the current route in `entries` */
+  index: number
+}
+
+export interface Navigator<Route> {
+  route: Route
+  history: RouterHistory<Route>
+  canGoBack: boolean
+  canGoForward: boolean
+  /** Goes to `route`, discarding any routes gone back from */
+  push: (route: Route) => void
+  /** Replaces the current route without adding to the history */
+  replace: (route: Route) => void
+  back: () => void
+  forward: () => void
+  /** Goes back or forward to the route at `index` in the history */
+  goTo: (index: number) => void
+}
+
+export interface RouterOptions {
+  /** Key names (e.g. `'escape'`) which go back. Default is none */
+  backKeys?: string[]
+  /** Key names which go forward. Default is none */
+  forwardKeys?: string[]
+}
+
+export interface Router<Route> {
+  /**
+   * Call in the root (or any ancestor) of the screens. Starts at `initialRoute`.
+   * Descendants can then use {@link useNavigate}, {@link useRoute} and {@link Outlet}
+   */
+  useProvide: (initialRoute: Route, options?: RouterOptions) => Navigator<Route>
+  /** Navigation functions and the current route of the nearest provided router. Throws if there is none */
+  useNavigate: () => Navigator<Route>
+  /** The current route of the nearest provided router. Throws if there is none */
+  useRoute: () => Route
+  /**
+   * Renders the current route with `render`. Only the current route's screen is created,
+   * so screens for other routes don't exist until navigated to (and are destroyed when navigated away from).
+   *
+   * ```jsx
+   * <router.Outlet render={route => {
+   *   switch (route.type) {
+   *     case 'home': return <Home />
+   *     case 'user': return <User id={route.id} />
+   *   }
+   * }} />
+   * ```
+   */
+  Outlet: (props: { render: (route: Route) => VNode }) => VNode
+}
+
+/**
+ * Creates a router, for multi-screen apps. `Route` is usually a union of strings or tagged objects.
+ *
+ * ```jsx
+ * type Route = { type: 'home' } | { type: 'user', id: number }
+ * const router = createRouter<Route>()
+ *
+ * const App = () => {
+ *   router.useProvide({ type: 'home' }, { backKeys: ['escape'] })
+ *   return <router.Outlet render={...} />
+ * }
+ *
+ * const Home = () => {
+ *   const navigate = router.useNavigate()
+ *   return <text onClick={() => navigate.push({ type: 'user', id: 1 })}>User 1</text>
+ * }
+ * ```
+ */
+export function createRouter<Route> (): Router<Route> {
+  const historyContext = createStateContext<RouterHistory<Route>>()
+
+  const mkNavigator = (history: { v: RouterHistory<Route> }): Navigator<Route> => {
+    const { entries, index } = history.v
+    return {
+      route: entries[index],
+      history: history.v,
+      canGoBack: index > 0,
+      canGoForward: index < entries.length - 1,
+      push: route => {
+        const { entries, index } = history.v
+        history.v = { entries: [...entries.slice(0, index + 1), route], index: index + 1 }
+      },
+      replace: route => {
+        const { entries, index } = history.v
+        history.v = { entries: [...entries.slice(0, index), route, ...entries.slice(index + 1)], index }
+      },
+      back: () => {
+        if (history.v.index > 0) {
+          history.v = { ...history.v, index: history.v.index - 1 }
+        }
+      },
+      forward: () => {
+        if (history.v.index < history.v.entries.length - 1) {
+          history.v = { ...history.v, index: history.v.index + 1 }
+        }
+      },
+      goTo: index => {
+        if (index >= 0 && index < history.v.entries.length && index !== history.v.index) {
+          history.v = { ...history.v, index }
+        }
+      }
+    }
+  }
+
+  const useNavigate = (): Navigator<Route> => {
+    const history = historyContext.useConsume()
+    if (history === null) {
+      throw new Error('no router provided: call router.useProvide in an ancestor component')
+    }
+    return mkNavigator(history)
+  }
+
+  return {
+    useProvide: (initialRoute, { backKeys, forwardKeys } = {}) => {
+      const history = historyContext.useProvide({ entries: [initialRoute], index: 0 })
+      useInput(key => {
+        if (key.name === undefined) {
+          return
+        }
+        if (backKeys?.includes(key.name) === true) {
+          mkNavigator(history).back()
+        } else if (forwardKeys?.includes(key.name) === true) {
+          mkNavigator(history).forward()
+        }
+      })
+      return mkNavigator(history)
+    },
+    useNavigate,
+    useRoute: () => useNavigate().route,
+    Outlet: ({ render }) => render(useNavigate().route)
+  }
+}
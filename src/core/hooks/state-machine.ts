@@ -0,0 +1,72 @@
+import { useEffect, useState } from 'core/hooks/intrinsic'
+
+/** States are strings, or objects tagged by `type` which can carry data (e.g. `{ type: 'error', message }`) */
+export type MachineState = string | { type: string }
+
+type StateName<State extends MachineState> = State extends string ? State : State extends { type: infer Type } ? Type : never
+
+export interface StateMachineSpec<State extends MachineState, Event> {
+  initial: State
+  /** Returns the next state, or null if `event` isn't allowed in `state` */
+  transition: (state: State, event: Event) => State | null
+  /**
+   * Effects run when entering a state (by name), after the component updates.
+   * If they return a function, it's called when leaving the state
+   */
+  // eslint-disable-next-line @typescript-eslint/no-invalid-void-type
+  onEnter?: { [Name in StateName<State>]?: (state: Extract<State, Name | { type: Name }>) => void | (() => void) }
+}
+
+export interface StateMachine<State extends MachineState, Event> {
+  state: State
+  /** The state's name: the state itself if it's a string, otherwise its `type` */
+  name: StateName<State>
+  /** Transitions and returns true if `event` is allowed in the current state, otherwise does nothing and returns false */
+  send: (event: Event) => boolean
+  /** Whether `event` is allowed in the current state */
+  can: (event: Event) => boolean
+}
+
+/** The state itself if it is a string, otherwise its `type` */
+export function stateName<State extends MachineState> (state: State): StateName<State> {
+  return (typeof state === 'string' ? state : state.type) as StateName<State>
+}
+
+/**
+ * An explicit state machine, for flows which would otherwise be scattered booleans.
+ *
+ * ```ts
+ * type State = 'connecting' | 'connected' | { type: 'error', message: string }
+ * type Event = { type: 'connected' } | { type: 'failed', message: string } | { type: 'retry' }
+ *
+ * const connection = useStateMachine<State, Event>({
+ *   initial: 'connecting',
+ *   transition: (state, event) => {
+ *     switch (stateName(state)) { ... }
+ *   },
+ *   onEnter: { connecting: () => { const socket = connect(...); return () => socket.close() } }
+ * })
+ * ```
+ */
+export function useStateMachine<State extends MachineState, Event> ({ initial, transition, onEnter }: StateMachineSpec<State, Event>): StateMachine<State, Event> {
+  const state = useState<State>(initial)
+  const name = stateName(state.v)
+
+  useEffect(() => {
+    return onEnter?.[name]?.(state.v as any)
+  }, { onChange: [name] })
+
+  return {
+    state: state.v,
+    name,
+    send: event => {
+      const nextState = transition(state.v, event)
+      if (nextState === null) {
+        return false
+      }
+      state.v = nextState
+      return true
+    },
+    can: event => transition(state.v, event) !== null
+  }
+}
@@ -0,0 +1,85 @@
+import { useEffect, useState } from 'core/hooks/intrinsic'
+import { useInterval } from 'core/hooks/extra'
+import { getRenderer } from 'core/component'
+
+export interface Stopwatch {
+  /** Total running time in milliseconds */
+  elapsedMs: number
+  isRunning: boolean
+  pause: () => void
+  resume: () => void
+  /** Sets the elapsed time to 0, keeping whether it's running */
+  reset: () => void
+}
+
+export interface Countdown extends Stopwatch {
+  remainingMs: number
+  isDone: boolean
+}
+
+/**
+ * A stopwatch which updates the component every `tickMs` while running.
+ * The elapsed time is computed from the renderer's clock (see {@link useNow}) rather than by adding up ticks, so it doesn't drift when ticks are late
+ */
+export function useStopwatch (tickMs: number = 100, startRunning: boolean = true): Stopwatch {
+  const now = getRenderer().now
+  // Elapsed = accumulatedMs + (now - startedAt) if running
+  const timing = useState<{ accumulatedMs: number, startedAt: number | null }>({
+    accumulatedMs: 0,
+    startedAt: startRunning ? now() : null
+  })
+  const tick = useState(0)
+
+  useInterval(tickMs, () => {
+    if (timing.v.startedAt !== null) {
+      // Setting the state updates the component, which recomputes elapsedMs
+      tick.v = now()
+    }
+  })
+
+  const getElapsedMs = (): number => {
+    const { accumulatedMs, startedAt } = timing.v
+    return accumulatedMs + (startedAt === null ? 0 : now() - startedAt)
+  }
+  return {
+    elapsedMs: getElapsedMs(),
+    isRunning: timing.v.startedAt !== null,
+    pause: () => {
+      if (timing.v.startedAt !== null) {
+        timing.v = { accumulatedMs: getElapsedMs(), startedAt: null }
+      }
+    },
+    resume: () => {
+      if (timing.v.startedAt === null) {
+        timing.v = { accumulatedMs: timing.v.accumulatedMs, startedAt: now() }
+      }
+    },
+    reset: () => {
+      timing.v = { accumulatedMs: 0, startedAt: timing.v.startedAt === null ? null : now() }
+    }
+  }
+}
+
+/**
+ * A {@link useStopwatch} which counts down from `durationMs`, and stops at 0.
+ * `onDone` is called once when it reaches 0 (and again each time it reaches 0 after a reset)
+ */
+export function useCountdown (durationMs: number, onDone?: () => void, tickMs: number = 100, startRunning: boolean = true): Countdown {
+  const stopwatch = useStopwatch(tickMs, startRunning)
+
+  const remainingMs = Math.max(0, durationMs - stopwatch.elapsedMs)
+  const isDone = remainingMs === 0
+  // In effects, so user code isn't called and state isn't updated while rendering
+  useEffect(() => {
+    if (isDone && stopwatch.isRunning) {
+      stopwatch.pause()
+    }
+  }, { onChange: [isDone, stopwatch.isRunning] })
+  useEffect(() => {
+    if (isDone) {
+      onDone?.()
+    }
+  }, { onChange: [isDone] })
+
+  return { ...stopwatch, remainingMs, isDone }
+}
@@ -1,7 +1,18 @@
 export * from 'core/view'
 export * from 'core/hooks'
+export * from 'core/components'
 export * from 'core/platform'
 export * from 'core/renderer'
+export * from 'core/render-log'
+export * from 'core/profile'
+export * from 'core/diagnostics'
+export * from 'core/hoc'
+export * from 'core/graphemes'
+export * from 'core/secret'
+export * from 'core/query'
+export * from 'core/automation'
+export * from 'core/accessibility'
+export * from 'core/deterministic'
 export { setGlobalComponentOpts } from 'core/component'
 export type { VComponent } from 'core/component'
 export type { Lens } from 'core/lens'
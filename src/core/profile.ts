@@ -0,0 +1,69 @@
+/**
+ * Component profiling: when `setGlobalComponentOpts({ profile: true })`,
+ * the time spent in each component's render function and effects is recorded, keyed by component path.
+ * Export with {@link Profile.toCollapsedStacks} and view with inferno or flamegraph.pl
+ */
+export interface ProfileEntry {
+  /** Component keys from the root, then `[render]` or `[effects]` */
+  stack: string[]
+  /** Total milliseconds across all calls, excluding nested sections (e.g. child components rendered during the render) */
+  durationMs: number
+  calls: number
+}
+
+export module Profile {
+  const ENTRIES: Map<string, ProfileEntry> = new Map()
+  /** Milliseconds spent in nested sections, for each section being measured */
+  const NESTED_MS: number[] = []
+
+  /** Calls `body` and records how long it took under `stack`, minus the time of sections measured inside it */
+  export function measure<T> (stack: string[], body: () => T): T {
+    NESTED_MS.push(0)
+    const start = performance.now()
+    try {
+      return body()
+    } finally {
+      const durationMs = performance.now() - start
+      const nestedMs = NESTED_MS.pop()!
+      if (NESTED_MS.length > 0) {
+        NESTED_MS[NESTED_MS.length - 1] += durationMs
+      }
+      record(stack, durationMs - nestedMs)
+    }
+  }
+
+  export function record (stack: string[], durationMs: number): void {
+    const id = stack.join('\0')
+    let entry = ENTRIES.get(id)
+    if (entry === undefined) {
+      entry = { stack, durationMs: 0, calls: 0 }
+      ENTRIES.set(id, entry)
+    }
+    entry.durationMs += durationMs
+    entry.calls++
+  }
+
+  /** Clears everything recorded so far */
+  export function reset (): void {
+    ENTRIES.clear()
+  }
+
+  /** Everything recorded since the last reset, slowest first */
+  export function entries (): ProfileEntry[] {
+    return [...ENTRIES.values()].sort((a, b) => b.durationMs - a.durationMs)
+  }
+
+  /**
+   * Everything recorded since the last reset in the collapsed stack format (`frame;frame;frame microseconds` per line)
+   * read by inferno and flamegraph.pl. Durations are self time, as they exclude children
+   */
+  export function toCollapsedStacks (): string {
+    return [...ENTRIES.values()]
+      .map(({ stack, durationMs }) => `${stack.map(escapeFrame).join(';')} ${Math.round(durationMs * 1000)}`)
+      .join('\n')
+  }
+
+  function escapeFrame (frame: string): string {
+    return frame.replace(/;/g, ':').replace(/\s/g, '_')
+  }
+}
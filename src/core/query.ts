@@ -0,0 +1,193 @@
+import type { VNode, VView, Rectangle } from 'core/view'
+
+/** A view found by {@link Renderer.query} */
+export interface QueryMatch {
+  viewId: number
+  view: VView
+  /** Keys of the components the view is in, outermost first */
+  components: readonly string[]
+  /** Where the view was last rendered, null if it's invisible, empty, or hasn't been rendered yet */
+  rect: Rectangle | null
+}
+
+/** `components` are the keys of the components the view is in, outermost first */
+export type QueryPredicate = (view: VView, components: readonly string[]) => boolean
+
+export interface QueryNode {
+  view: VView
+  /** Keys of the components which directly resolve to this view (e.g. `<Counter>` whose node is a `<box>`) */
+  ownComponents: string[]
+}
+
+interface CompoundSelector {
+  /** View type or component key, null for `*` */
+  name: string | null
+  key: string | null
+  contains: string[]
+}
+
+const VIEW_TYPES = new Set(['box', 'fragment', 'text', 'color', 'border', 'source', 'pixi'])
+
+export module Query {
+  /**
+   * Parses a selector into a predicate. Selectors are whitespace-separated (descendant) compounds of:
+   *
+   * - `box`, `text`, ...: the view's type
+   * - `Counter`: the view is the node of a component with this key (by default components' keys are their function names)
+   * - `*`: any view
+   * - `#key`: the view's key
+   * - `:contains('Save')`: the view's text, or the text of its descendants, contains the string
+   *
+   * e.g. `Dialog box:contains('Save')`, `text#title`, `*:contains('Error')`
+   */
+  export function parse (selector: string): (path: readonly QueryNode[]) => boolean {
+    const compounds = splitCompounds(selector).map(parseCompound)
+    if (compounds.length === 0) {
+      throw new Error(`empty selector: ${selector}`)
+    }
+    return path => {
+      // Last compound matches the view itself, earlier compounds match ancestors in order
+      if (!matches(compounds[compounds.length - 1], path[path.length - 1])) {
+        return false
+      }
+      let pathIndex = path.length - 2
+      for (let i = compounds.length - 2; i >= 0; i--) {
+        while (pathIndex >= 0 && !matches(compounds[i], path[pathIndex])) {
+          pathIndex--
+        }
+        if (pathIndex < 0) {
+          return false
+        }
+        pathIndex--
+      }
+      return true
+    }
+  }
+
+  /** Converts a typed predicate into the form used by {@link run} */
+  export function fromPredicate (predicate: QueryPredicate): (path: readonly QueryNode[]) => boolean {
+    return path => predicate(path[path.length - 1].view, path.flatMap(node => node.ownComponents))
+  }
+
+  /** Finds every view under `root` matching `matcher`, in tree order. `getRect` gets where the view was last rendered */
+  export function run (root: VNode, matcher: (path: readonly QueryNode[]) => boolean, getRect: (view: VView) => Rectangle | null): QueryMatch[] {
+    const results: QueryMatch[] = []
+    const path: QueryNode[] = []
+    const visit = (node: VNode): void => {
+      const ownComponents: string[] = []
+      while (node.type === 'component') {
+        if (node.node === null) {
+          return
+        }
+        ownComponents.push(node.key)
+        node = node.node
+      }
+      path.push({ view: node, ownComponents })
+      if (matcher(path)) {
+        results.push({
+          viewId: node.id,
+          view: node,
+          components: path.flatMap(node => node.ownComponents),
+          rect: getRect(node)
+        })
+      }
+      if (node.type === 'box' || node.type === 'fragment') {
+        node.children.forEach(visit)
+      }
+      path.pop()
+    }
+    visit(root)
+    return results
+  }
+
+  /** The text of the view and its descendants, joined by newlines */
+  export function textContent (node: VNode): string {
+    while (node.type === 'component') {
+      if (node.node === null) {
+        return ''
+      }
+      node = node.node
+    }
+    if (node.type === 'text') {
+      return node.text
+    } else if (node.type === 'box' || node.type === 'fragment') {
+      return node.children.map(textContent).filter(text => text !== '').join('\n')
+    } else {
+      return ''
+    }
+  }
+
+  /** The center of the match's rect, to send synthetic clicks, or null if it doesn't have a rect */
+  export function center (match: QueryMatch): { x: number, y: number } | null {
+    if (match.rect === null) {
+      return null
+    }
+    return {
+      x: Math.floor(match.rect.left + match.rect.width / 2),
+      y: Math.floor(match.rect.top + match.rect.height / 2)
+    }
+  }
+
+  function matches (compound: CompoundSelector, node: QueryNode): boolean {
+    if (compound.name !== null) {
+      if (VIEW_TYPES.has(compound.name) ? node.view.type !== compound.name : !node.ownComponents.includes(compound.name)) {
+        return false
+      }
+    }
+    if (compound.key !== null && node.view.key !== compound.key) {
+      return false
+    }
+    if (compound.contains.length > 0) {
+      const text = textContent(node.view)
+      if (!compound.contains.every(substring => text.includes(substring))) {
+        return false
+      }
+    }
+    return true
+  }
+
+  /** Splits on whitespace outside of quotes */
+  function splitCompounds (selector: string): string[] {
+    const compounds: string[] = []
+    let current = ''
+    let quote: string | null = null
+    for (const char of selector) {
+      if (quote !== null) {
+        current += char
+        if (char === quote) {
+          quote = null
+        }
+      } else if (char === '\'' || char === '"') {
+        current += char
+        quote = char
+      } else if (/\s/.test(char)) {
+        if (current !== '') {
+          compounds.push(current)
+          current = ''
+        }
+      } else {
+        current += char
+      }
+    }
+    if (quote !== null) {
+      throw new Error(`unterminated string in selector: ${selector}`)
+    }
+    if (current !== '') {
+      compounds.push(current)
+    }
+    return compounds
+  }
+
+  function parseCompound (compound: string): CompoundSelector {
+    const match = /^(\*|[A-Za-z_$][\w$-]*)?(?:#([\w$-]+))?((?::contains\((?:'[^']*'|"[^"]*")\))*)$/.exec(compound)
+    if (match === null) {
+      throw new Error(`invalid selector: ${compound}`)
+    }
+    const [, name, key, contains] = match
+    return {
+      name: name === undefined || name === '*' ? null : name,
+      key: key ?? null,
+      contains: [...contains.matchAll(/:contains\((?:'([^']*)'|"([^"]*)")\)/g)].map(([, single, double]) => single ?? double)
+    }
+  }
+}
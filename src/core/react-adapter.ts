@@ -1,4 +1,4 @@
-import { VView } from 'core/view/view'
+import { VBox, VView } from 'core/view/view'
 import { intrinsics, JSXIntrinsics, VJSX } from 'core/view/jsx'
 import { VComponent } from 'core/component'
 import { IntoArray } from '@raycenity/misc-ts'
@@ -43,10 +43,24 @@ function createElement <T extends VView, Props extends { key?: string }, Childre
     }
   } else {
     // Component
-    return VComponent(props.key ?? element.name, { ...props, children }, element)
+    const { visible, ...props2 } = props as Props & { visible?: boolean }
+    const construct = visible === false ? hideComponent(element) : element
+    return VComponent(props.key ?? element.name, { ...props2, children } as unknown as Props, construct)
   }
 }
 
+/**
+ * Makes the component's node invisible: it's still constructed, so the component and its children keep their state,
+ * but it's skipped by layout and rendering
+ */
+function hideComponent<Props> (construct: (props: Props) => VNode): (props: Props) => VNode {
+  // Copy properties so pixi components still work
+  return Object.assign((props: Props): VNode => {
+    const node = construct(props)
+    return node.type === 'component' ? VBox([node], { visible: false }) : { ...node, visible: false }
+  }, construct)
+}
+
 export const React = { createElement }
 // @ts-expect-error
 globalThis.React = React
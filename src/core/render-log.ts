@@ -0,0 +1,103 @@
+import type { BoundingBox, Rectangle } from 'core/view'
+
+/**
+ * One line of the JSON-lines render log: one view rendered (or reused from the cache) in one frame.
+ * Enable with `setGlobalComponentOpts({ renderLogSink: RenderLog.jsonl(write) })`
+ */
+export interface RenderLogEntry {
+  /** Index of the frame (the renderer's generation when it started rendering) */
+  frame: number
+  /** Component keys, view types and keys, and child indices from the root, e.g. `/RootComponent/box[2]/Counter/text`. Stable across runs */
+  path: string
+  /** Not stable across runs */
+  viewId: number
+  /** Hash of the path and view structure. Stable across runs */
+  hash: string
+  /**
+   * - `'hit'`: reused the cached render
+   * - `'same-bounds'`: reused the cached render because the view's bounds didn't change, even though its parent's did
+   * - `'miss'`: rendered
+   */
+  cache: 'hit' | 'same-bounds' | 'miss'
+  /** null if the view is invisible */
+  bounds: BoundingBox | null
+  /** The rendered rectangle, null if empty */
+  rect: Rectangle | null
+  /** Milliseconds to render this view including its children */
+  durationMs: number
+  /** Milliseconds to render this view excluding its children */
+  selfDurationMs: number
+}
+
+export interface RenderLogSummaryEntry {
+  path: string
+  renders: number
+  hits: number
+  misses: number
+  durationMs: number
+  selfDurationMs: number
+}
+
+export interface RenderLogSummary {
+  frames: number
+  /** Sorted by `selfDurationMs`, slowest first */
+  paths: RenderLogSummaryEntry[]
+}
+
+export module RenderLog {
+  /** A render log sink which writes each entry as a line of JSON */
+  export function jsonl (write: (line: string) => void): (entry: RenderLogEntry) => void {
+    return entry => write(`${JSON.stringify(entry)}\n`)
+  }
+
+  /** A render log sink which collects entries into an array, e.g. to pass to {@link summarize} */
+  export function collect (entries: RenderLogEntry[]): (entry: RenderLogEntry) => void {
+    return entry => { entries.push(entry) }
+  }
+
+  /** Parses a JSON-lines render log, ignoring blank lines */
+  export function parse (jsonl: string): RenderLogEntry[] {
+    return jsonl.split('\n').filter(line => line.trim() !== '').map(line => JSON.parse(line))
+  }
+
+  /** Totals renders, cache hits and durations per path, to find hot components */
+  export function summarize (entries: RenderLogEntry[]): RenderLogSummary {
+    const frames = new Set<number>()
+    const paths = new Map<string, RenderLogSummaryEntry>()
+    for (const entry of entries) {
+      frames.add(entry.frame)
+      let summary = paths.get(entry.path)
+      if (summary === undefined) {
+        summary = { path: entry.path, renders: 0, hits: 0, misses: 0, durationMs: 0, selfDurationMs: 0 }
+        paths.set(entry.path, summary)
+      }
+      summary.renders++
+      if (entry.cache === 'miss') {
+        summary.misses++
+      } else {
+        summary.hits++
+      }
+      summary.durationMs += entry.durationMs
+      summary.selfDurationMs += entry.selfDurationMs
+    }
+    return {
+      frames: frames.size,
+      paths: [...paths.values()].sort((a, b) => b.selfDurationMs - a.selfDurationMs)
+    }
+  }
+
+  /** Formats the `limit` slowest paths of the summary as a table */
+  export function formatSummary (summary: RenderLogSummary, limit: number = 20): string {
+    const lines = [`${summary.frames} frames`, 'self ms   total ms  renders  misses  path']
+    for (const entry of summary.paths.slice(0, limit)) {
+      lines.push([
+        entry.selfDurationMs.toFixed(2).padStart(8),
+        entry.durationMs.toFixed(2).padStart(9),
+        entry.renders.toString().padStart(8),
+        entry.misses.toString().padStart(7),
+        entry.path
+      ].join('  '))
+    }
+    return lines.join('\n')
+  }
+}
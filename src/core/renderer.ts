@@ -1,20 +1,145 @@
 import { VView } from 'core/view/view'
-import { Size } from 'core/view'
+import { Size, VNode } from 'core/view'
+import { Key } from '@raycenity/misc-ts'
+import type { QueryMatch, QueryPredicate } from 'core/query'
+import type { AccessibilityTree } from 'core/accessibility'
+
+/**
+ * Where an input event came from: `'terminal'` and `'browser'` are the platform's own input,
+ * `'synthetic'` is from {@link Renderer.sendInput}, anything else is a source added via {@link Renderer.addInputSource}
+ */
+export type InputSource = string
+
+export type InputHandler = (key: Key, source: InputSource) => void
+
+export interface MouseInput {
+  type: 'press' | 'release' | 'move' | 'wheel-up' | 'wheel-down'
+  /** null for wheel events, and moves without a button held */
+  button: 'left' | 'middle' | 'right' | null
+  /** In columns */
+  x: number
+  /** In rows */
+  y: number
+}
+
+/** What the renderer's platform supports, so components can adapt instead of relying on features which may not work */
+export interface Capabilities {
+  /**
+   * - `'native'`: images are shown as real pixels
+   * - `'approximate'`: images are approximated with colored characters
+   * - `'none'`: images aren't shown
+   */
+  images: 'native' | 'approximate' | 'none'
+  /** Number of colors which can be shown. Colors are converted to the nearest supported color */
+  colors: 2 | 16 | 256 | 'truecolor'
+  /** Whether mouse input ({@link Renderer.sendMouse}) is received */
+  mouse: boolean
+  /** Whether a real text cursor is shown at {@link TextAttrs.cursor} */
+  cursor: boolean
+  /** Whether {@link TextAttrs.link} is clickable */
+  links: boolean
+  /** Whether {@link Renderer.writeClipboard} works */
+  clipboard: boolean
+  /** Frames rendered per second (at most) */
+  fps: number
+}
+
+/** Timing of frames rendered by the renderer's timer (not {@link Renderer.forceRerender} calls) */
+export interface FrameStats {
+  /** Frames rendered */
+  frames: number
+  /** Frames which took longer than the frame interval */
+  overruns: number
+  /** Intervals skipped because a frame was still rendering, so changes were shown late */
+  droppedFrames: number
+  lastFrameMs: number
+  averageFrameMs: number
+  maxFrameMs: number
+  /** The FPS the renderer aims for */
+  targetFps: number
+  /** The FPS the renderer currently runs at, lower than `targetFps` while backing off */
+  currentFps: number
+}
+
+export interface ShutdownSummary<Frame = unknown> {
+  /** Whether there were changes which were never rendered */
+  hadPendingRerender: boolean
+  /** The number of times the renderer rerendered */
+  generation: number
+  /** The last frame rendered (platform-specific), or null if it never rendered */
+  lastFrame: Frame | null
+  /**
+   * Input, mouse, paste and exit handlers which components registered but didn't remove when they were destroyed.
+   * In debug mode, they're also reported with {@link Diagnostics.warn}
+   */
+  leakedHandlers: number
+}
 
 export interface Renderer {
   forceRerender: () => void
   reroot: <Props>(props?: Props, root?: (props: Props) => VView) => void
+  hotReload: <Props>(root: (props: Props) => VNode) => void
   show: () => void
   hide: () => void
   dispose: () => void
+  useInput: (handler: InputHandler) => () => void
+  captureInput: (handler: InputHandler) => () => void
+  sendInput: (key: Key, source?: InputSource) => void
+  addInputSource: (source: InputSource, subscribe: (send: (key: Key) => void) => () => void) => () => void
+  writeClipboard: (text: string) => void
+  sendMouse: (event: MouseInput) => void
+  sendPaste: (text: string) => void
+  getGeneration: () => number
+  waitForRerender: (afterGeneration?: number) => Promise<number>
+  shutdown: (flush?: boolean) => ShutdownSummary
+  shutdownAsync: (flush?: boolean) => Promise<ShutdownSummary>
+  getCapabilities: () => Capabilities
+  getLastInputTime: () => number
+  getFrameStats: () => FrameStats
+  resetFrameStats: () => void
+  random: () => number
+  now: () => number
+  chordTimeout: number
+  addExitBlocker: (blocker: () => boolean | Promise<boolean>) => () => void
+  requestShutdown: (flush?: boolean) => Promise<ShutdownSummary | null>
+  setLayoutDebug: (enabled: boolean) => void
+  query: (selector: string | QueryPredicate) => QueryMatch[]
+  accessibilityTree: () => AccessibilityTree
+  getScreenCells: () => string[][]
+  getViewportSize: () => Size
 }
 
 export interface CoreRenderOptions {
   fps?: number
+  /** Source of random numbers in [0, 1) for {@link useRandom}. Default is `Math.random`, see {@link Deterministic.seededRandom} */
+  random?: () => number
+  /** Current time in milliseconds for {@link useNow}, timers and idle detection. Default is `Date.now`, see {@link Deterministic} */
+  now?: () => number
+  /**
+   * When frames keep taking longer than the frame interval, temporarily lower the FPS (down to 1),
+   * and raise it back once frames are fast again. Default is false
+   */
+  adaptiveFps?: boolean
+  /** Draw the layout debug overlay, see {@link Renderer.setLayoutDebug} */
+  layoutDebug?: boolean
+  /**
+   * Collapse consecutive mouse moves and resizes which arrive before the next tick into the latest one,
+   * so bursts don't run handlers for every intermediate value. Other input sends pending moves and resizes first,
+   * so ordering is preserved. Default is true
+   */
+  coalesceInput?: boolean
+  /** Milliseconds to wait for the next key of a multi-key binding in {@link useKeymap}. Default is 1000 */
+  chordTimeout?: number
 }
 
 export const DEFAULT_CORE_RENDER_OPTIONS: Required<CoreRenderOptions> = {
-  fps: 20
+  fps: 20,
+  random: Math.random,
+  now: Date.now,
+  adaptiveFps: false,
+  layoutDebug: false,
+  coalesceInput: true,
+  chordTimeout: 1000
 }
 
 export const DEFAULT_COLUMN_SIZE: Size = {
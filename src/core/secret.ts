@@ -0,0 +1,41 @@
+const INSPECT = Symbol.for('nodejs.util.inspect.custom')
+
+/**
+ * A string which shouldn't be logged, e.g. a password.
+ * It shows as `<secret>` when converted to a string or JSON, inspected by `console.log`, or logged in the build tree.
+ * Get the actual value with `reveal()`, and show it with a `mask` on the text so it isn't stored in the view.
+ *
+ * JavaScript strings are immutable, so unlike a zeroizing buffer, the value stays in memory until it's garbage collected.
+ * `clear()` drops this secret's reference to it.
+ */
+export class Secret {
+  #value: string
+
+  constructor (value: string) {
+    this.#value = value
+  }
+
+  reveal (): string {
+    return this.#value
+  }
+
+  clear (): void {
+    this.#value = ''
+  }
+
+  get length (): number {
+    return this.#value.length
+  }
+
+  toString (): string {
+    return '<secret>'
+  }
+
+  toJSON (): string {
+    return '<secret>'
+  }
+
+  [INSPECT] (): string {
+    return '<secret>'
+  }
+}
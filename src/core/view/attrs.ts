@@ -1,14 +1,65 @@
 import { BoundingBox, Bounds, BoundsSpec, Size } from 'core/view/bounds'
 import { BorderStyle } from 'core/view/border-style'
 import { Color, ColorSpec, LCHColor, RGBColor } from 'core/view/color'
+import { Style } from 'core/view/style'
 import type { DisplayObject } from 'pixi.js'
 import { DelayedSubLayout } from 'core'
 import { CustomDelayedSubLayout } from 'core/view/sub-layout'
+import type { MouseInput } from 'core/renderer'
+import type { FocusHandle } from 'core/hooks/focus'
+
+/**
+ * `zIndex` of overlays like {@link CopyMode}, {@link SearchOverlay} and {@link HelpOverlay}, which are drawn above everything else.
+ * `zIndex` only orders siblings, so put overlays directly in the root component
+ */
+export const OVERLAY_Z_INDEX = 1000000
 
 export interface CommonAttrs {
   readonly bounds?: Bounds
   readonly visible?: boolean
   readonly key?: string
+  /** Called when the view is clicked, if it's the topmost view with `onClick` under the mouse */
+  readonly onClick?: (event: MouseInput) => void
+  /**
+   * Clicking the view focuses this (see {@link useFocus}). It's hit-tested against the view's rendered rect like `onClick`,
+   * and a view with both gets focused, then `onClick`
+   */
+  readonly focus?: FocusHandle
+  /** Called when the mouse wheel is scrolled over the view, if it's the topmost view with `onWheel` under the mouse */
+  readonly onWheel?: (event: MouseInput) => void
+  /**
+   * Moves the view (and its children) after layout, so it doesn't affect siblings.
+   * In columns and rows, rounded to integers
+   */
+  readonly offset?: { x: number, y: number }
+  /**
+   * Draws the view and its children above siblings (and their children) with a lower `zIndex`, regardless of their `z`.
+   * Siblings with the same `zIndex` are ordered by `z`, then earlier siblings are drawn above later ones. Default is 0
+   */
+  readonly zIndex?: number
+  /** Darkens the view's (and its children's) colors, from 0 (unchanged) to 1 (black). Useful for disabled states */
+  readonly dim?: number
+  /** Don't warn if the view's width or height is zero or negative, e.g. if it's intentionally collapsed */
+  readonly allowEmpty?: boolean
+  /** Which slot of the parent component the view goes in, see {@link Children.slots} */
+  readonly slot?: string
+  /** What the view is to assistive tooling, see {@link AccessibilityNode}. Default is inferred from the view */
+  readonly role?: AccessibilityRole
+  /** Name of the view for assistive tooling, e.g. a label for an icon-only button */
+  readonly label?: string
+  /** Current value for assistive tooling, e.g. of an input or slider. Default for text is the text */
+  readonly value?: string
+  /** Whether the view has keyboard focus, for assistive tooling. Default is `focus.isFocused` if `focus` is set */
+  readonly focused?: boolean
+}
+
+export type AccessibilityRole =
+  'group' | 'text' | 'heading' | 'button' | 'link' | 'image' | 'textbox' | 'checkbox' | 'slider' |
+  'list' | 'listitem' | 'menu' | 'menuitem' | 'dialog' | 'progressbar' | 'tab' | 'tablist' | 'none'
+
+/** Fragments are laid out by their parent, so they don't have bounds */
+export interface FragmentAttrs extends CommonAttrs {
+  readonly bounds?: undefined
 }
 
 export interface BoxAttrs extends CommonAttrs {
@@ -18,11 +69,40 @@ export interface BoxAttrs extends CommonAttrs {
 }
 
 export interface TextAttrs extends CommonAttrs {
+  /** Default is `style.color` */
   readonly color: Color | null
+  /** Background, weight and decorations, see {@link Style.merge} to combine styles from themes and props */
+  readonly style?: Style
   readonly wrapMode?: 'word' | 'char' | 'clip'
+  /**
+   * Makes the text a hyperlink to this URL.
+   * In terminals which support OSC 8 it can be clicked, in others it's plain text.
+   * In the browser clicking it opens the URL. For a link which can be focused and activated with enter, see {@link Link}
+   */
+  readonly link?: string
+  /**
+   * Shows the real terminal cursor at this position, relative to the text's top-left.
+   * If multiple views have a cursor, the one rendered first wins.
+   */
+  readonly cursor?: TextCursor
+  /**
+   * Shows this character in place of each character of the text, e.g. `'*'` for a password.
+   * The text is replaced when the view is created, so the real text isn't stored in the view or logged
+   */
+  readonly mask?: string
+}
+
+export type CursorShape = 'bar' | 'underline' | 'block'
+
+export interface TextCursor {
+  x: number
+  y: number
+  /** Default is 'bar' */
+  shape?: CursorShape
 }
 
 export interface ColorAttrs extends CommonAttrs {
+  /** If translucent (`alpha` < 1), it's blended over what's below, and in the terminal, text below shows through */
   readonly color: Color
 }
 
@@ -1,5 +1,6 @@
 import { assert } from '@raycenity/misc-ts'
 import { ParentSubLayout } from 'core/view/sub-layout'
+import { Diagnostics } from 'core/diagnostics'
 
 export type LayoutDirection = 'horizontal' | 'vertical' | 'overlap'
 
@@ -11,7 +12,7 @@ export type Measurement =
 
 type Measurement2 = 'prev' | Measurement3
 type Measurement3 = `${number}%` | Measurement4
-type Measurement4 = `${number}px` | Measurement5
+type Measurement4 = `${number}px` | `${number}vw` | `${number}vh` | Measurement5
 type Measurement5 = `${number}` | number
 
 export type LayoutPosition1D =
@@ -49,9 +50,14 @@ export interface ParentBounds {
   boundingBox: BoundingBox
   sublayout: ParentSubLayout
   columnSize: Size
+  /** Size of the root (the terminal or canvas), which `vw` and `vh` measurements are percentages of */
+  viewport: Size
 }
 
-export type Bounds = (parent: ParentBounds, prevSibling: Rectangle | null) => BoundingBox
+export type Bounds = ((parent: ParentBounds, prevSibling: Rectangle | null) => BoundingBox) & {
+  /** The spec the bounds were created from, shown by the layout debug overlay */
+  spec?: BoundsSpec
+}
 
 export interface FullBoundsSpec {
   layout?: LayoutPosition
@@ -67,7 +73,7 @@ export interface FullBoundsSpec {
 export type BoundsSpec = FullBoundsSpec
 
 export function Bounds (spec: BoundsSpec): Bounds {
-  return (parent, prevSibling) => ({
+  return Object.assign((parent: ParentBounds, prevSibling: Rectangle | null): BoundingBox => ({
     x: applyLayoutX(parent, prevSibling, spec.layout, reifyX(parent, 'not-applicable', spec.x)),
     y: applyLayoutY(parent, prevSibling, spec.layout, reifyY(parent, 'not-applicable', spec.y)),
     z: spec.z ?? parent.boundingBox.z + Bounds.BOX_Z,
@@ -75,7 +81,7 @@ export function Bounds (spec: BoundsSpec): Bounds {
     anchorY: spec.anchorY ?? 0,
     width: spec.width === undefined ? undefined : reifyX(parent, prevSibling?.width ?? null, spec.width),
     height: spec.height === undefined ? undefined : reifyY(parent, prevSibling?.height ?? null, spec.height)
-  })
+  }), { spec })
 }
 
 function reifyX (parent: ParentBounds, prevSibling: number |'not-applicable' | null, x: Measurement | undefined): number {
@@ -94,6 +100,10 @@ function reifyX (parent: ParentBounds, prevSibling: number |'not-applicable' | n
     return (parent.boundingBox.width * parseFloat(x) / 100)
   } else if (x.endsWith('px')) {
     return parseFloat(x) / parent.columnSize.width
+  } else if (/^-?\d*\.?\d+vw$/.test(x)) {
+    return parent.viewport.width * parseFloat(x) / 100
+  } else if (/^-?\d*\.?\d+vh$/.test(x)) {
+    return parent.viewport.height * parseFloat(x) / 100
   } else if (x === 'prev') {
     if (prevSibling === 'not-applicable') {
       throw new Error('can\'t use \'prev\' for position or gap')
@@ -129,6 +139,10 @@ function reifyY (parent: ParentBounds, prevSibling: number | 'not-applicable' |
     return (parent.boundingBox.height * parseFloat(y) / 100)
   } else if (y.endsWith('px')) {
     return parseFloat(y) / parent.columnSize.height
+  } else if (/^-?\d*\.?\d+vw$/.test(y)) {
+    return parent.viewport.width * parseFloat(y) / 100
+  } else if (/^-?\d*\.?\d+vh$/.test(y)) {
+    return parent.viewport.height * parseFloat(y) / 100
   } else if (y === 'prev') {
     if (prevSibling === 'not-applicable') {
       throw new Error('can\'t use \'prev\' for position or gap')
@@ -169,7 +183,7 @@ function applyLayoutX (parent: ParentBounds, prevSibling: Rectangle | null, layo
           return reified + parent.boundingBox.x
         case undefined:
           if (prevSibling !== null) {
-            console.warn('no layout direction specified with multiple children, defaulting to overlap (applyLayoutX)')
+            Diagnostics.warn('no layout direction specified with multiple children, defaulting to overlap (applyLayoutX)')
           }
           return reified + parent.boundingBox.x
       }
@@ -197,7 +211,7 @@ function applyLayoutY (parent: ParentBounds, prevSibling: Rectangle | null, layo
           return reified + parent.boundingBox.y
         case undefined:
           if (prevSibling !== null) {
-            console.warn('no layout direction specified with multiple children, defaulting to overlap (applyLayoutY)')
+            Diagnostics.warn('no layout direction specified with multiple children, defaulting to overlap (applyLayoutY)')
           }
           return reified + parent.boundingBox.y
       }
@@ -224,6 +238,19 @@ export function getLayoutBoundingBoxTop (bounds: BoundingBox): number {
   }
 }
 
+export module Measurement {
+  /** Whether the measurement depends on the viewport size (uses `vw` or `vh`) */
+  export function usesViewport (measurement: Measurement | undefined): boolean {
+    return typeof measurement === 'string' && /v[wh]\b/.test(measurement)
+  }
+}
+
+export module Size {
+  export function equals (a: Size, b: Size): boolean {
+    return a.width === b.width && a.height === b.height
+  }
+}
+
 export module ParentBounds {
   export function equals (a: ParentBounds, b: ParentBounds): boolean {
     return JSON.stringify(a) === JSON.stringify(b)
@@ -235,6 +262,18 @@ export module Rectangle {
     return JSON.stringify(a) === JSON.stringify(b)
   }
 
+  export function translate (rect: Rectangle, offset: { x: number, y: number }): Rectangle {
+    return {
+      ...rect,
+      left: rect.left + offset.x,
+      top: rect.top + offset.y
+    }
+  }
+
+  export function contains (rect: Rectangle, point: { x: number, y: number }): boolean {
+    return point.x >= rect.left && point.x < rect.left + rect.width && point.y >= rect.top && point.y < rect.top + rect.height
+  }
+
   export function union (a: Rectangle | null, b: Rectangle | null): Rectangle | null {
     if (a === null) {
       return b
@@ -279,6 +318,10 @@ export module Rectangle {
 }
 
 export module BoundingBox {
+  export function equals (a: BoundingBox, b: BoundingBox): boolean {
+    return JSON.stringify(a) === JSON.stringify(b)
+  }
+
   export function toRectangle (bounds: BoundingBox & Size): Rectangle
   export function toRectangle (bounds: BoundingBox, size: Size): Rectangle
   export function toRectangle (bounds: BoundingBox, size?: Size): Rectangle {
@@ -0,0 +1,52 @@
+import { VJSX } from 'core/view/jsx'
+import { VNode } from 'core/view/node'
+
+/** Utilities for the `children` prop of components, which can contain nulls and nested arrays (e.g. from `map`) */
+export module Children {
+  /** Flattens and removes nulls */
+  export function toArray (children: VJSX | undefined): VNode[] {
+    return VJSX.collapse(children)
+  }
+
+  export function count (children: VJSX | undefined): number {
+    return toArray(children).length
+  }
+
+  export function map<T> (children: VJSX | undefined, f: (child: VNode, index: number) => T): T[] {
+    return toArray(children).map(f)
+  }
+
+  /** Throws unless there is exactly one child */
+  export function only (children: VJSX | undefined): VNode {
+    const array = toArray(children)
+    if (array.length !== 1) {
+      throw new Error(`expected exactly one child, got ${array.length}`)
+    }
+    return array[0]
+  }
+
+  /**
+   * Distributes children into named slots by their `slot` attribute (or prop, for components).
+   * Children without a slot go in `default`. Throws if a child has a slot which isn't in `names`.
+   *
+   * e.g. a `Panel` which puts `<text slot='header'>` above a divider and the other children below:
+   * `const { header, default: body } = Children.slots(children, ['header'])`
+   */
+  export function slots<Slot extends string> (children: VJSX | undefined, names: readonly Slot[]): Record<Slot | 'default', VNode[]> {
+    const result = { default: [] } as unknown as Record<Slot | 'default', VNode[]>
+    for (const name of names) {
+      result[name] = []
+    }
+    for (const child of toArray(children)) {
+      const slot: string | undefined = child.type === 'component' ? child.props?.slot : child.slot
+      if (slot === undefined) {
+        result.default.push(child)
+      } else if (slot in result) {
+        result[slot as Slot].push(child)
+      } else {
+        throw new Error(`unknown slot: ${slot} (expected one of ${names.join(', ')})`)
+      }
+    }
+    return result
+  }
+}
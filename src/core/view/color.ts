@@ -176,6 +176,23 @@ export module Color {
     }
   }
 
+  /** Relative luminance (as in WCAG), from 0 (black) to 1 (white). Ignores alpha */
+  export function luminance (color: ColorSpec): number {
+    const { red, green, blue } = toRGB(Color(color))
+    const linear = (channel: number): number => channel <= 0.03928 ? channel / 12.92 : Math.pow((channel + 0.055) / 1.055, 2.4)
+    return 0.2126 * linear(red) + 0.7152 * linear(green) + 0.0722 * linear(blue)
+  }
+
+  /**
+   * Contrast ratio (as in WCAG) between two colors, from 1 (identical) to 21 (black and white).
+   * WCAG recommends at least 4.5 for text and 3 for large text and UI elements
+   */
+  export function contrastRatio (foreground: ColorSpec, background: ColorSpec): number {
+    const lhs = luminance(foreground)
+    const rhs = luminance(background)
+    return (Math.max(lhs, rhs) + 0.05) / (Math.min(lhs, rhs) + 0.05)
+  }
+
   export function invert (color: ColorSpec): Color {
     color = toLCH(Color(color))
     return {
@@ -0,0 +1,24 @@
+import { VBox } from 'core/view/view'
+import { VNode } from 'core/view/node'
+
+/**
+ * An invisible, empty node. Use in place of a child which isn't shown,
+ * so that the box's other children stay at the same positions and keep their state
+ */
+export function placeholder (key?: string): VNode {
+  return VBox([], { key, visible: false })
+}
+
+/**
+ * `mkNode()` if `condition`, otherwise a {@link placeholder} with `key`.
+ * Prefer this over `condition && mkNode()` in a box with other children,
+ * because `null` is removed from the children and shifts the ones after it
+ */
+export function when (condition: boolean, mkNode: () => VNode, key?: string): VNode {
+  return condition ? mkNode() : placeholder(key)
+}
+
+/** `mkThen()` if `condition`, otherwise `mkElse()`. Always one node, so siblings never shift */
+export function either (condition: boolean, mkThen: () => VNode, mkElse: () => VNode): VNode {
+  return condition ? mkThen() : mkElse()
+}
@@ -1,9 +1,13 @@
 export * from 'core/view/attrs'
 export * from 'core/view/border-style'
+export * from 'core/view/children'
 export * from 'core/view/bounds'
 export * from 'core/view/color'
+export * from 'core/view/conditional'
 export * from 'core/view/jsx'
 export * from 'core/view/node'
+export * from 'core/view/palette'
+export * from 'core/view/style'
 export * from 'core/view/pixi-node'
 export * from 'core/view/sub-layout'
 export * from 'core/view/view'
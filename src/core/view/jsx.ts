@@ -6,6 +6,8 @@ import {
   JSXTextAttrs
 } from 'core/view/attrs'
 import { VBorder, VBox, VColor, VView, VSource, VText } from 'core/view/view'
+import { Diagnostics } from 'core/diagnostics'
+import { getCurrentDirection } from 'core/hooks/direction'
 import { ExplicitPartial, IntoArray } from '@raycenity/misc-ts'
 import { jsxToNormalAttrs, jsxColorToNormalAttrs } from 'core/view/jsx-helpers'
 import { DelayedSubLayout, VNode } from 'core'
@@ -41,6 +43,13 @@ export interface JSXIntrinsics {
 
 export interface JSXIntrinsicAttributes {
   key?: string | number
+  /**
+   * If false, the element is skipped by layout and rendering.
+   * Unlike not creating it, a hidden component stays mounted, so it keeps its state
+   */
+  visible?: boolean
+  /** Which slot of the parent component the element goes in, see {@link Children.slots} */
+  slot?: string
 }
 
 export const intrinsics: {
@@ -58,8 +67,12 @@ export const intrinsics: {
     const sublayout: ExplicitPartial<DelayedSubLayout> = { direction, gap, store, keep, custom }
 
     const children_ = VJSX.collapse(children)
+    // Only the order, see WritingDirection
+    if (direction === 'horizontal' && getCurrentDirection() === 'rtl') {
+      children_.reverse()
+    }
     if (children_.length > 1 && direction === undefined) {
-      console.warn('direction must be specified for multiple children')
+      Diagnostics.warn('direction must be specified for multiple children')
     }
 
     return VBox(children_, { bounds, visible, key, sublayout, ...attrs })
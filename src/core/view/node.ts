@@ -14,7 +14,7 @@ export module VNode {
     updatePath += `/${node.key ?? ''}`
     if (node.type === 'component') {
       VComponent.update(node, updatePath)
-    } else if (node.type === 'box') {
+    } else if (node.type === 'box' || node.type === 'fragment') {
       node.children.forEach((child, index) => {
         const updateSubpath = `${updatePath}[${index}]`
         update(child, updateSubpath)
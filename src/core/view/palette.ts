@@ -0,0 +1,68 @@
+import { HexColor } from 'core/view/color'
+
+/** Colors by purpose, so components don't hardcode colors and apps can switch palettes */
+export interface Palette {
+  foreground: HexColor
+  background: HexColor
+  muted: HexColor
+  accent: HexColor
+  info: HexColor
+  success: HexColor
+  warning: HexColor
+  error: HexColor
+}
+
+export module Palette {
+  /**
+   * The Okabe-Ito palette: 8 colors which are distinguishable with every common type of color blindness
+   * (https://jfly.uni-koeln.de/color/)
+   */
+  export const OKABE_ITO = {
+    black: '#000000',
+    orange: '#E69F00',
+    skyBlue: '#56B4E9',
+    bluishGreen: '#009E73',
+    yellow: '#F0E442',
+    blue: '#0072B2',
+    vermillion: '#D55E00',
+    reddishPurple: '#CC79A7'
+  } as const
+
+  export const DEFAULT: Palette = {
+    foreground: '#FFFFFF',
+    background: '#000000',
+    muted: '#808080',
+    accent: '#5F87FF',
+    info: '#00AFFF',
+    success: '#00D75F',
+    warning: '#FFD700',
+    error: '#FF5F5F'
+  }
+
+  /**
+   * Uses {@link OKABE_ITO} so that success, warning and error are distinguishable without red-green discrimination.
+   * Still pair status colors with text or symbols, since no palette works for everyone
+   */
+  export const COLOR_BLIND_SAFE: Palette = {
+    foreground: '#FFFFFF',
+    background: '#000000',
+    muted: '#999999',
+    accent: OKABE_ITO.reddishPurple,
+    info: OKABE_ITO.skyBlue,
+    success: OKABE_ITO.bluishGreen,
+    warning: OKABE_ITO.yellow,
+    error: OKABE_ITO.vermillion
+  }
+
+  /** Every color has a contrast ratio of at least 7 against the background */
+  export const HIGH_CONTRAST: Palette = {
+    foreground: '#FFFFFF',
+    background: '#000000',
+    muted: '#BBBBBB',
+    accent: '#87D7FF',
+    info: '#87D7FF',
+    success: '#87FF87',
+    warning: '#FFFF5F',
+    error: '#FF8787'
+  }
+}
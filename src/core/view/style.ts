@@ -0,0 +1,39 @@
+import { ColorSpec } from 'core/view/color'
+
+/**
+ * How text looks, separate from what it says, so components can accept styles from themes and their parents.
+ * Unset attributes are inherited when merging (see {@link Style.merge}), `null` colors explicitly reset to the default
+ */
+export interface Style {
+  readonly color?: ColorSpec | null
+  readonly background?: ColorSpec | null
+  readonly bold?: boolean
+  readonly italic?: boolean
+  readonly underline?: boolean
+  /** Faint text. Unlike the `dim` view attribute, doesn't change colors */
+  readonly dim?: boolean
+  readonly strikethrough?: boolean
+}
+
+export module Style {
+  export const NONE: Style = {}
+
+  /**
+   * Combines `styles`, later ones overriding earlier ones per attribute, e.g.
+   * `Style.merge(theme.text, { bold: true }, props.style)`
+   */
+  export function merge (...styles: Array<Style | undefined>): Style {
+    const result: { -readonly [Key in keyof Style]: Style[Key] } = {}
+    for (const style of styles) {
+      if (style === undefined) {
+        continue
+      }
+      for (const [key, value] of Object.entries(style) as Array<[keyof Style, any]>) {
+        if (value !== undefined) {
+          result[key] = value
+        }
+      }
+    }
+    return result
+  }
+}
@@ -1,8 +1,10 @@
-import { BorderAttrs, BoxAttrs, ColorAttrs, PixiAttrs, SourceAttrs, TextAttrs } from 'core/view/attrs'
+import { BorderAttrs, BoxAttrs, ColorAttrs, FragmentAttrs, PixiAttrs, SourceAttrs, TextAttrs } from 'core/view/attrs'
 import type { DisplayObject } from 'pixi.js'
 import { VNode } from 'core'
+import { Graphemes } from 'core/graphemes'
+import { Color } from 'core/view/color'
 
-export type VView = VBox | VText | VColor | VBorder | VSource | VPixi<any>
+export type VView = VBox | VFragment | VText | VColor | VBorder | VSource | VPixi<any>
 
 interface VViewCommon {
   readonly id: number
@@ -14,6 +16,15 @@ export interface VBox extends BoxAttrs, VViewCommon {
   readonly children: readonly VNode[]
 }
 
+/**
+ * Multiple nodes without a wrapper: the children are laid out as if they were the parent's children in its place,
+ * e.g. returning `<>...</>` from a component
+ */
+export interface VFragment extends FragmentAttrs, VViewCommon {
+  readonly type: 'fragment'
+  readonly children: readonly VNode[]
+}
+
 export interface VText extends TextAttrs, VViewCommon {
   readonly type: 'text'
   readonly text: string
@@ -38,6 +49,12 @@ export interface VPixi<Pixi extends DisplayObject> extends PixiAttrs<Pixi>, VVie
 }
 
 export function VText (text: string, attrs: TextAttrs): VText {
+  if (attrs.mask !== undefined) {
+    text = text.split('\n').map(line => attrs.mask!.repeat(Graphemes.split(line).length)).join('\n')
+  }
+  if (attrs.color === null && attrs.style?.color !== undefined && attrs.style.color !== null) {
+    attrs = { ...attrs, color: Color(attrs.style.color) }
+  }
   return { id: VNode.nextId(), type: 'text', text, ...attrs }
 }
 
@@ -45,6 +62,10 @@ export function VBox (children: VNode[], attrs: BoxAttrs): VBox {
   return { id: VNode.nextId(), type: 'box', children, ...attrs }
 }
 
+export function VFragment (children: VNode[], attrs: FragmentAttrs): VFragment {
+  return { id: VNode.nextId(), type: 'fragment', children, ...attrs }
+}
+
 export function VColor (attrs: ColorAttrs): VColor {
   return { id: VNode.nextId(), type: 'color', ...attrs }
 }
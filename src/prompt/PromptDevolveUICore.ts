@@ -2,7 +2,7 @@ import { Renderer, VNode } from 'core'
 import type { TerminalRenderOptions } from 'renderer/cli'
 import type { BrowserRenderOptions } from 'renderer/web'
 import { VComponent } from 'core/component'
-import { PromptArgs, PromptReplacedError, PromptReturn, PromptSpec, PromptTimeoutError } from 'prompt/prompt'
+import { PromptArgs, PromptProgress, PromptReplacedError, PromptReturn, PromptSpec, PromptStep, PromptTimeoutError } from 'prompt/prompt'
 import { DevolveUICore } from 'core/DevolveUICore'
 import { Lens } from 'core/lens'
 
@@ -12,6 +12,8 @@ export type RenderOptions =
 
 export interface PromptProps<PromptKeys extends string | number | symbol> {
   prompts: { [Key in PromptKeys]?: PromptSpec }
+  /** Set while {@link PromptDevolveUICore.promptSteps} runs, e.g. to show with {@link WizardSteps} or {@link Breadcrumbs} */
+  progress?: PromptProgress
 }
 
 export abstract class PromptDevolveUICore<Props extends PromptProps<PromptKeys>, PromptKeys extends string | number | symbol> extends DevolveUICore<Props> {
@@ -31,11 +33,11 @@ export abstract class PromptDevolveUICore<Props extends PromptProps<PromptKeys>,
 
   override setProps (newProps: Omit<Props, keyof PromptProps<any>>): void {
     for (const _key in newProps) {
-      if (_key === 'prompts') {
-        throw new Error('can\'t set prompts directly')
+      if (_key === 'prompts' || _key === 'progress') {
+        throw new Error(`can't set ${_key} directly`)
       }
     }
-    super.setProps({ ...newProps as Props, prompts: this.props.prompts })
+    super.setProps({ ...newProps as Props, prompts: this.props.prompts, progress: this.props.progress })
   }
 
   async prompt<Key extends PromptKeys>(key: Key, promptArgs: PromptArgs<Props['prompts'][Key]>, earlyCancelPing?: () => boolean): PromptReturn<Props['prompts'][Key]> {
@@ -74,6 +76,26 @@ export abstract class PromptDevolveUICore<Props extends PromptProps<PromptKeys>,
     return await Promise.race([promptPromise, earlyCancelPromise])
   }
 
+  /**
+   * Runs each step in order, and resolves with their results. Before each, sets `progress` in the props to the step,
+   * so the root component can show it with {@link WizardSteps}, which takes the same `steps` and `current`. Afterwards `progress` is removed
+   */
+  async promptSteps<T>(steps: Array<PromptStep<T>>): Promise<T[]> {
+    const labels = steps.map(({ label }) => label)
+    const results: T[] = []
+    try {
+      for (const [index, step] of steps.entries()) {
+        this.props.progress = { steps: labels, current: index }
+        this.updateProps()
+        results.push(await step.run())
+      }
+    } finally {
+      delete this.props.progress
+      this.updateProps()
+    }
+    return results
+  }
+
   protected override propsLens<T extends object>(props: T): Lens<T> {
     const lens = super.propsLens(props)
     Lens.onSet(lens, (newValue, debugPath) => {
@@ -27,3 +27,17 @@ export type PromptArgs<T extends PromptSpec | undefined> = Omit<T, 'resolve' | '
 
 export type PromptReturn<T extends PromptSpec | undefined> =
   Promise<T extends PromptSpec ? FirstParameter<T['resolve']> : never>
+
+/** Progress through a sequence of prompts, e.g. a wizard, see {@link PromptDevolveUICore.promptSteps} */
+export interface PromptProgress {
+  /** Step labels */
+  steps: string[]
+  /** Index of the current step, or `steps.length` once they're all done */
+  current: number
+}
+
+export interface PromptStep<T> {
+  label: string
+  /** Prompts for the step, e.g. with {@link PromptDevolveUICore.prompt} */
+  run: () => Promise<T>
+}
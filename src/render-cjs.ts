@@ -14,7 +14,7 @@ try {
     PlatformRendererImpl = require('renderer/web').BrowserRendererImpl
   } else if (PLATFORM === 'cli') {
     const cliModule = require('renderer/cli')
-    cliModule.initModule({ readline: require('readline') })
+    cliModule.initModule({ readline: require('readline'), stream: require('stream') })
     PlatformRendererImpl = cliModule.TerminalRendererImpl
   } else {
     // noinspection ExceptionCaughtLocallyJS
@@ -30,6 +30,9 @@ try {
 
 export type { RenderOptions, PromptProps }
 export * from 'prompt/prompt'
+export * from 'renderer/cli/remote'
+export * from 'renderer/cli/accessibility-server'
+export * from 'renderer/mock'
 
 export class DevolveUI<Props extends object> extends DevolveUICore<Props> {
   protected override mkRenderer (root: () => VComponent, opts?: RenderOptions): Renderer {
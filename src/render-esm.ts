@@ -10,8 +10,8 @@ const PlatformRendererImpl: new (root: () => VComponent, opts?: RenderOptions) =
   PLATFORM === 'web'
     ? import('renderer/web').then(module => module.BrowserRendererImpl)
     : PLATFORM === 'cli'
-      ? Promise.all([import('renderer/cli'), import('readline')]).then(([module, readline]) => {
-        module.initModule({ readline })
+      ? Promise.all([import('renderer/cli'), import('readline'), import('stream')]).then(([module, readline, stream]) => {
+        module.initModule({ readline, stream })
         return module.TerminalRendererImpl
       })
       : Promise.reject(new Error(`Unsupported platform: ${PLATFORM}`))
@@ -20,6 +20,9 @@ const PlatformRendererImpl: new (root: () => VComponent, opts?: RenderOptions) =
 
 export type { RenderOptions, PromptProps }
 export * from 'prompt/prompt'
+export * from 'renderer/cli/remote'
+export * from 'renderer/cli/accessibility-server'
+export * from 'renderer/mock'
 
 export class DevolveUI<Props extends object> extends DevolveUICore<Props> {
   protected override mkRenderer (root: () => VComponent, opts?: RenderOptions): Renderer {
@@ -13,7 +13,42 @@ export function CharColor (type: CharColorType, openEscape: string, closeEscape:
   }
 }
 
+/** Translucent backgrounds have their alpha between these, after the open escape. It's removed before output */
+const ALPHA = /\u{FFF5}([^\u{FFF6}]*)\u{FFF6}/u
+const TRUECOLOR_BG = /\x1b\[48;2;(\d+);(\d+);(\d+)m/
+
 export module CharColor {
+  /** A background which is blended with the background below it when collapsing (see {@link blend}), `alpha` in (0, 1) */
+  export function translucentBg (openEscape: string, closeEscape: string, alpha: number): CharColor {
+    return CharColor('bg', `${openEscape}\u{FFF5}${alpha}\u{FFF6}`, closeEscape)
+  }
+
+  /** 1 unless `color` is from {@link translucentBg} */
+  export function alpha (color: CharColor): number {
+    const match = ALPHA.exec(color)
+    return match === null ? 1 : parseFloat(match[1])
+  }
+
+  /**
+   * The translucent background `top` over the background `bottom`.
+   * Only truecolor backgrounds can be blended, otherwise `top` is treated as opaque
+   */
+  export function blend (top: CharColor, bottom: CharColor): CharColor {
+    const topMatch = TRUECOLOR_BG.exec(top)
+    const bottomMatch = TRUECOLOR_BG.exec(bottom)
+    const topAlpha = alpha(top)
+    if (topMatch === null || bottomMatch === null) {
+      return top.replace(ALPHA, '')
+    }
+    const bottomAlpha = alpha(bottom)
+    const resultAlpha = topAlpha + bottomAlpha * (1 - topAlpha)
+    const [red, green, blue] = [1, 2, 3].map(i =>
+      Math.round((parseInt(topMatch[i]) * topAlpha + parseInt(bottomMatch[i]) * bottomAlpha * (1 - topAlpha)) / resultAlpha)
+    )
+    const openEscape = `\x1b[48;2;${red};${green};${blue}m`
+    return resultAlpha < 1 ? translucentBg(openEscape, close(top), resultAlpha) : CharColor('bg', openEscape, close(top))
+  }
+
   export function has (type: CharColorType, string: string): boolean {
     switch (type) {
       case 'fg':
@@ -70,7 +105,7 @@ export module CharColor {
       open += color.substring(fgIndex + 1, color.indexOf('\u{FFF2}'))
     }
     if (bgIndex !== -1) {
-      open += color.substring(bgIndex + 1, color.indexOf('\u{FFF4}'))
+      open += color.substring(bgIndex + 1, color.indexOf('\u{FFF4}')).replace(ALPHA, '')
     }
     return open
   }
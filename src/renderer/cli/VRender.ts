@@ -6,9 +6,13 @@ import { CharColor, TRANSPARENT } from 'renderer/cli/CharColor'
  * If the character is multi-width, then the next character will be empty.
  * If the character is \u{FFF0} it is transparent (the character under will be used).
  * If the character contains \u{FFF1} and \u{FFF2}, it is a background (characters above will also have the background unless they also contain a background)
+ * If the background is translucent (see {@link CharColor.translucentBg}), it's blended with the background below, and a space shows the character below
  */
 export type VRender = string[][]
 
+/** Characters which can be in a URL (RFC 3986) as they are. Others are percent-encoded */
+const URL_CHARACTER = /^[A-Za-z0-9\-._~:/?#[\]@!$&'()*+,;=%]$/
+
 export module VRender {
   export function addColor (vrender: VRender, color: CharColor): void {
     for (const line of vrender) {
@@ -18,6 +22,37 @@ export module VRender {
     }
   }
 
+  /**
+   * Wraps each character in an OSC 8 hyperlink. Terminals which don't support OSC 8 ignore it.
+   * The URL is sanitized (see {@link sanitizeUrl}), and if nothing is left there's no link
+   */
+  export function addLink (vrender: VRender, url: string): void {
+    url = sanitizeUrl(url)
+    if (url === '') {
+      return
+    }
+    for (const line of vrender) {
+      for (let x = 0; x < line.length; x++) {
+        // Empty = the second half of a wide character
+        if (line[x] !== '') {
+          line[x] = `\x1b]8;;${url}\x07${line[x]}\x1b]8;;\x07`
+        }
+      }
+    }
+  }
+
+  /**
+   * Removes control characters (C0, DEL and C1) and lone surrogates, and percent-encodes other characters which can't be in a URL,
+   * so the URL can't end the OSC 8 sequence early and write its own escape sequences to the terminal
+   */
+  export function sanitizeUrl (url: string): string {
+    let result = ''
+    for (const char of url.replace(/[\p{Cc}\p{Cs}]/gu, '')) {
+      result += URL_CHARACTER.test(char) ? char : encodeURIComponent(char)
+    }
+    return result
+  }
+
   export function translate1 (vrender: VRender, bounds: BoundingBox): void {
     const width = bounds.width ?? getWidth(vrender)
     const height = bounds.height ?? getHeight(vrender)
@@ -34,34 +69,54 @@ export module VRender {
 
     for (const line of vrender) {
       if (line.length > 0) {
+        if (xOffset < 0) {
+          line.splice(0, -xOffset)
+        }
         for (let x = 0; x < xOffset; x++) {
           line.unshift(TRANSPARENT)
         }
       }
     }
+    if (yOffset < 0) {
+      vrender.splice(0, -yOffset)
+    }
     for (let y = 0; y < yOffset; y++) {
       vrender.unshift([])
     }
   }
 
-  export function collapse (textMatrix: Record<number, VRender>): string[][] {
-    for (const key of Object.keys(textMatrix)) {
-      if (isNaN(parseFloat(key))) {
-        delete textMatrix[key as any]
+  /** Scales truecolor colors by `1 - dim`, and makes characters without a foreground color faint */
+  export function dim (vrender: VRender, dim: number): void {
+    const scale = (channel: string): number => Math.round(parseInt(channel) * (1 - dim))
+    const faint = CharColor('fg', '\x1b[2m', '\x1b[22m')
+    for (const line of vrender) {
+      for (let x = 0; x < line.length; x++) {
+        const char = line[x]
+        if (char === TRANSPARENT || char === '') {
+          continue
+        }
+        line[x] = char.replace(/\x1b\[(38|48);2;(\d+);(\d+);(\d+)m/g, (_, type: string, red: string, green: string, blue: string) =>
+          `\x1b[${type};2;${scale(red)};${scale(green)};${scale(blue)}m`
+        )
+        if (!CharColor.has('fg', line[x])) {
+          line[x] += faint
+        }
       }
     }
+  }
 
-    if (Object.values(textMatrix).length === 0) {
+  /** `layers` are topmost first */
+  export function collapse (layers: VRender[]): string[][] {
+    if (layers.length === 0) {
       return []
     }
 
     // Array length not width
-    const length = Math.max(...Object.values(textMatrix).map(getWidth))
-    const height = Math.max(...Object.values(textMatrix).map(getHeight))
-    const matrixSorted = Object.entries(textMatrix).sort(([lhs], [rhs]) => Number(rhs) - Number(lhs)).map(([, lines]) => lines)
+    const length = Math.max(...layers.map(getWidth))
+    const height = Math.max(...layers.map(getHeight))
 
     const result: string[][] = Array(height).fill(null).map(() => Array(length).fill(TRANSPARENT))
-    for (const lines of matrixSorted) {
+    for (const lines of layers) {
       for (let y = 0; y < lines.length; y++) {
         const line = lines[y]
         const resultLine = result[y]
@@ -71,9 +126,22 @@ export module VRender {
           if (resultChar === TRANSPARENT) {
             // fall through
             resultLine[x] = char
-          } else if (!CharColor.has('bg', resultChar) && CharColor.has('bg', char)) {
-            // add color
-            resultLine[x] += CharColor.get('bg', char)!
+            continue
+          } else if (char === TRANSPARENT) {
+            continue
+          }
+          const resultBg = CharColor.get('bg', resultChar)
+          const bg = CharColor.get('bg', char)
+          if (resultBg === null) {
+            if (bg !== null) {
+              // Characters without a background show the background below
+              resultLine[x] += bg
+            }
+          } else if (CharColor.alpha(resultBg) < 1) {
+            const blendedBg = bg === null ? resultBg : CharColor.blend(resultBg, bg)
+            // A translucent background without a character (e.g. from a color view) shows the character below
+            const top = CharColor.remove(resultChar) === ' ' && !CharColor.has('fg', resultChar) ? char : resultChar
+            resultLine[x] = CharColor.remove(top) + (CharColor.get('fg', top) ?? '') + blendedBg
           }
         }
       }
@@ -0,0 +1,47 @@
+import type { Server } from 'net'
+import type { Renderer } from 'core/renderer'
+import { listenServer, RemoteAddress } from 'renderer/cli/remote'
+
+/**
+ * Serves the renderer's accessibility tree at `address` (a Unix socket path, port, or host and port),
+ * for external assistive tooling or tests.
+ *
+ * Each client is sent the current {@link AccessibilityTree} as one line of JSON when it connects,
+ * then another line after every rerender, until it disconnects or the renderer is disposed.
+ */
+export async function listenAccessibility (renderer: Renderer, address: RemoteAddress): Promise<Server> {
+  const net = await import('net')
+
+  const server = net.createServer(socket => {
+    let isConnected = true
+    socket.on('close', () => {
+      isConnected = false
+    })
+    socket.on('error', () => {
+      isConnected = false
+    })
+
+    const send = (): void => {
+      socket.write(`${JSON.stringify(renderer.accessibilityTree())}\n`)
+    }
+    const sendOnRerender = async (): Promise<void> => {
+      let generation = renderer.getGeneration()
+      send()
+      while (isConnected) {
+        try {
+          generation = await renderer.waitForRerender(generation)
+        } catch {
+          // Renderer disposed
+          socket.end()
+          return
+        }
+        if (isConnected) {
+          send()
+        }
+      }
+    }
+    void sendOnRerender()
+  })
+  await listenServer(server, address)
+  return server
+}
@@ -0,0 +1,62 @@
+/** How long an unfinished sequence is held back before it's sent as is, e.g. because it was the escape key */
+const ESCAPE_TIMEOUT_MS = 50
+
+/**
+ * Joins escape sequences which are split across chunks of input (e.g. a mouse report read in two parts), so they can be matched with regexes.
+ * If a chunk ends with what may be the start of a sequence, that's held back and prepended to the next chunk,
+ * or sent on its own if no chunk comes soon
+ */
+export class EscapeBuffer {
+  private pending: string = ''
+  private timer: ReturnType<typeof setTimeout> | null = null
+
+  /**
+   * `isPartial` is whether the end of the data, starting at an escape, may be the start of a sequence.
+   * `onData` is called with the data, never ending in a partial sequence unless it timed out
+   */
+  constructor (
+    private readonly isPartial: (tail: string) => boolean,
+    private readonly onData: (data: string) => void
+  ) {}
+
+  write (data: string): void {
+    this.clearTimer()
+    data = this.pending + data
+    this.pending = ''
+    // The earliest escape, since a partial sequence can contain escapes (e.g. an unfinished ST)
+    for (let start = data.indexOf('\x1b'); start !== -1; start = data.indexOf('\x1b', start + 1)) {
+      if (this.isPartial(data.slice(start))) {
+        this.pending = data.slice(start)
+        data = data.slice(0, start)
+        this.timer = setTimeout(() => this.flush(), ESCAPE_TIMEOUT_MS)
+        break
+      }
+    }
+    if (data !== '') {
+      this.onData(data)
+    }
+  }
+
+  /** Sends what's held back, e.g. when the input ends */
+  flush (): void {
+    this.clearTimer()
+    const pending = this.pending
+    this.pending = ''
+    if (pending !== '') {
+      this.onData(pending)
+    }
+  }
+
+  /** Drops what's held back */
+  dispose (): void {
+    this.clearTimer()
+    this.pending = ''
+  }
+
+  private clearTimer (): void {
+    if (this.timer !== null) {
+      clearTimeout(this.timer)
+      this.timer = null
+    }
+  }
+}
@@ -1,20 +1,25 @@
 import type { Interface } from 'readline'
 import type { ReadStream, WriteStream } from 'tty'
-import { BorderStyle, BoundingBox, Color, Rectangle, Size, VView } from 'core/view'
-import { CoreRenderOptions } from 'core/renderer'
+import { BorderStyle, BoundingBox, Color, CursorShape, Rectangle, Size, Style, VView } from 'core/view'
+import { Capabilities, CoreRenderOptions, MouseInput } from 'core/renderer'
 import { VComponent } from 'core/component'
-import { Key, range, Strings } from '@raycenity/misc-ts'
-import { terminalImage } from 'renderer/cli/terminal-image-min'
-import { CoreAssetCacher, RendererImpl, VRenderBatch } from 'renderer/common'
+import { Diagnostics } from 'core/diagnostics'
+import { Graphemes } from 'core/graphemes'
+import { Key, range } from '@raycenity/misc-ts'
+import { getImageSupport, ImageFormat, terminalImage } from 'renderer/cli/terminal-image-min'
+import { CoreAssetCacher, CursorPosition, RendererImpl, VRenderBatch } from 'renderer/common'
 import { chalk } from '@raycenity/chalk-cross'
 import { VRender } from 'renderer/cli/VRender'
 import { CharColor, TRANSPARENT } from 'renderer/cli/CharColor'
+import { EscapeBuffer } from 'renderer/cli/escape-buffer'
 import type { DisplayObject } from 'pixi.js'
 
 let readline: typeof import('readline')
+let stream: typeof import('stream')
 
-export function initModule (imports: { readline: typeof import('readline') }): void {
+export function initModule (imports: { readline: typeof import('readline'), stream: typeof import('stream') }): void {
   readline = imports.readline
+  stream = imports.stream
 }
 
 export interface TerminalRenderOptions extends CoreRenderOptions {
@@ -26,20 +31,104 @@ export interface TerminalRenderOptions extends CoreRenderOptions {
    * Default is 'strict', use 'loose' for simple CLI apps e.g. those without images
    */
   positionStrictness?: 'strict' | 'loose'
+  /**
+   * Additional outputs which receive the same frames as `output`, e.g. a recording file or a socket.
+   * Each mirror has its own `positionStrictness` (default is 'strict').
+   * Mirrors don't affect the root dimensions, those always come from `output`.
+   */
+  mirrors?: TerminalMirror[]
+  /**
+   * Enables bracketed paste mode, so pasted text is sent to `usePaste` handlers all at once,
+   * instead of to `useInput` handlers one key at a time. Default is false
+   */
+  bracketedPaste?: boolean
+  /**
+   * Enables mouse reporting, so `onClick` and `useMouse` work (including moves while a button is held).
+   * While enabled, most terminals only select text when you hold shift. Default is false
+   */
+  mouse?: boolean
+  /**
+   * How to show images. Default is detected: from environment variables at startup (see `getImageSupport`),
+   * then if those don't indicate support, by asking the terminal whether it supports sixel.
+   * If the terminal reports an error showing an image, images fall back to `'fallback'`
+   */
+  imageFormat?: ImageFormat
+}
+
+export interface TerminalMirror {
+  output: NodeJS.WritableStream
+  positionStrictness?: 'strict' | 'loose'
+}
+
+type PositionStrictness = 'strict' | 'loose'
+
+/** Primary device attributes (DA1) response: ESC [ ? attributes c. Attribute 4 is sixel support */
+const DA1_RESPONSE = /\x1b\[\?([\d;]*)c/g
+/** Kitty graphics protocol response: ESC _ G ... ; message ESC \. Messages other than OK are errors */
+const KITTY_GRAPHICS_RESPONSE = /\x1b_G[^;]*;([^\x1b]*)\x1b\\/g
+
+/** SGR (1006) mouse report: ESC [ < code ; x ; y M (press) or m (release) */
+const SGR_MOUSE = /\x1b\[<(\d+);(\d+);(\d+)([Mm])/g
+
+/** Start of a sequence above, which may be completed by the next chunk of input */
+const PARTIAL_SEQUENCE = /^\x1b(\[([<?][\d;]*)?|_(G[^\x1b]*\x1b?)?)?$/
+
+function decodeMouse (code: number, x: number, y: number, isRelease: boolean): MouseInput | null {
+  // Terminal coordinates are 1-based
+  x--
+  y--
+  if ((code & 32) !== 0) {
+    // Motion, sent while a button is held; button code 3 means none is
+    return { type: 'move', button: (['left', 'middle', 'right'] as const)[code & 3] ?? null, x, y }
+  } else if ((code & 64) !== 0) {
+    return { type: (code & 1) === 0 ? 'wheel-up' : 'wheel-down', button: null, x, y }
+  } else {
+    const button = (['left', 'middle', 'right'] as const)[code & 3]
+    if (button === undefined) {
+      return null
+    }
+    return { type: isRelease ? 'release' : 'press', button, x, y }
+  }
+}
+
+/** SGR codes to turn each style attribute on and off */
+const STYLE_CODES: Array<[keyof Style, number, number]> = [
+  ['bold', 1, 22],
+  ['dim', 2, 22],
+  ['italic', 3, 23],
+  ['underline', 4, 24],
+  ['strikethrough', 9, 29]
+]
+
+/** CSI and OSC escape sequences, which {@link VRender.collapse} adds to cells for colors and links */
+const ESCAPE_SEQUENCE = /\x1b\[[0-9;?]*[ -/]*[@-~]|\x1b\][^\x07]*\x07/g
+
+/** DECSCUSR codes for steady cursors */
+const CURSOR_SHAPE_CODES: Record<CursorShape, number> = {
+  block: 2,
+  underline: 4,
+  bar: 6
 }
 
 class AssetCacher extends CoreAssetCacher {
-  static async image (path: string, width?: number, height?: number): Promise<VRender> {
+  static async image (path: string, width?: number, height?: number, format?: ImageFormat): Promise<VRender> {
     try {
-      return await terminalImage.file(path, { width, height })
+      return await terminalImage.file(path, { width, height, format })
     } catch (exception) {
+      if (format !== undefined && format !== 'fallback' && format !== 'ascii') {
+        // The protocol's encoder may not support this image
+        Diagnostics.warn(`failed to render image ${path} as ${format}, falling back to characters: ${String(exception)}`)
+        return await AssetCacher.image(path, width, height, 'fallback')
+      }
       console.error('Failed to load image', path, exception)
       return [['?']]
     }
   }
 
-  getImage (path: string, width?: number, height?: number): [VRender | null, (didResolve: () => void) => void] {
-    return this.getAsync(path, async path => await AssetCacher.image(path, width, height))
+  getImage (path: string, width?: number, height?: number, format?: ImageFormat): [VRender | null, (didResolve: () => void) => void] {
+    // The same image is encoded differently at each size and format
+    const key = `${path}\0${width ?? ''}x${height ?? ''}\0${format ?? ''}`
+    return this.getAsync(key, async () => await AssetCacher.image(path, width, height, format))
   }
 }
 
@@ -47,14 +136,25 @@ export class TerminalRendererImpl extends RendererImpl<VRender, AssetCacher> {
   private readonly interact: Interface
   private readonly input: ReadStream
   private readonly output: WriteStream
+  /** Input without mouse sequences, which readline parses into keys */
+  private readonly keys: NodeJS.ReadWriteStream
+  /** Joins sequences split across chunks of input before they're removed */
+  private readonly sequences: EscapeBuffer = new EscapeBuffer(tail => PARTIAL_SEQUENCE.test(tail), data => this.onSequences(data))
+
+  private readonly outputs: Array<Required<TerminalMirror>>
+
+  private readonly bracketedPaste: boolean
+  private readonly mouse: boolean
+  private imageFormat: ImageFormat
+  /** Text pasted so far while in bracketed paste, or null if not pasting */
+  private pasteBuffer: string | null = null
 
   private linesOutput: number = 0
-  private readonly positionStrictness: 'strict' | 'loose'
 
   constructor (root: () => VComponent, opts: TerminalRenderOptions = {}) {
     super(new AssetCacher(), opts)
 
-    let { input, output, interact, positionStrictness } = opts
+    let { input, output, interact, positionStrictness, mirrors, bracketedPaste, mouse, imageFormat } = opts
 
     input = input ?? process.stdin
     output = output ?? process.stdout
@@ -64,54 +164,140 @@ export class TerminalRendererImpl extends RendererImpl<VRender, AssetCacher> {
     this.interact = interact
     this.input = input
     this.output = output
-    this.positionStrictness = positionStrictness
+    this.outputs = [
+      { output, positionStrictness },
+      ...(mirrors ?? []).map(mirror => ({ positionStrictness: 'strict' as const, ...mirror }))
+    ]
 
     // Configure input
     if (this.input.isTTY) {
       this.input.setRawMode(true)
     }
     this.input.setEncoding('utf8')
-    readline.emitKeypressEvents(this.input)
+    // readline doesn't understand mouse sequences, so we remove them before it parses keys
+    this.keys = new stream.PassThrough({ encoding: 'utf8' })
+    this.input.addListener('data', this.onData)
+    readline.emitKeypressEvents(this.keys)
+    this.keys.addListener('keypress', this.onKeypress)
+
+    // Configure output
+    this.output.on('resize', this.onResize)
+    this.bracketedPaste = bracketedPaste ?? false
+    if (this.bracketedPaste) {
+      this.output.write('\x1b[?2004h')
+    }
+    this.mouse = mouse ?? false
+    if (this.mouse) {
+      this.output.write('\x1b[?1002h\x1b[?1006h')
+    }
+    this.imageFormat = imageFormat ?? getImageSupport()
+    if (imageFormat === undefined && this.imageFormat === 'fallback' && this.input.isTTY && this.output.isTTY) {
+      // Ask for device attributes, the response is handled in onData
+      this.output.write('\x1b[c')
+    }
 
     this.finishInit(root)
   }
 
   protected override clear (): void {
     if (this.linesOutput !== 0) {
-      if (this.positionStrictness === 'loose') {
-        this.output.moveCursor(0, -this.linesOutput)
-        this.output.clearScreenDown()
+      // Only loose outputs need to be cleared, strict outputs clear the screen when writing
+      // (this is what moveCursor(0, -linesOutput) and clearScreenDown() write)
+      const clear = `\x1b[${this.linesOutput}A\x1b[J`
+      for (const { output, positionStrictness } of this.outputs) {
+        if (positionStrictness === 'loose') {
+          output.write(clear)
+        }
       }
       this.linesOutput = 0
     }
   }
 
   protected override writeRender (render: VRenderBatch<VRender>): void {
-    const lines = VRender.collapse(render)
+    const lines = VRender.collapse(render.layers.map(layer => layer.render))
+
+    // Encode each frame once per strictness, and write it in one call, so mirroring is cheap
+    const frames: Partial<Record<PositionStrictness, string>> = {}
+    for (const { output, positionStrictness } of this.outputs) {
+      const frame = frames[positionStrictness] ?? TerminalRendererImpl.encodeFrame(lines, render.cursor, positionStrictness)
+      frames[positionStrictness] = frame
+      output.write(frame)
+    }
+    this.linesOutput += lines.length
+  }
 
-    if (this.positionStrictness === 'strict') {
+  /** Rewrites only the rows which changed. Loose outputs can only be rewritten from the top, so if there are any, rewrites everything */
+  protected override writeRenderDiff (render: VRenderBatch<VRender>, dirtyRects: Rectangle[]): void {
+    if (this.outputs.some(({ positionStrictness }) => positionStrictness === 'loose')) {
+      this.clear()
+      this.writeRender(render)
+      return
+    }
+
+    const lines = VRender.collapse(render.layers.map(layer => layer.render))
+    const rows = new Set<number>()
+    for (const rect of dirtyRects) {
+      for (let y = Math.max(0, Math.floor(rect.top)); y < Math.min(this.output.rows, Math.ceil(rect.top + rect.height)); y++) {
+        rows.add(y)
+      }
+    }
+
+    let frame = ''
+    for (const y of [...rows].sort((a, b) => a - b)) {
+      // Clear the row in case it got shorter, then write it after one cursor move instead of positioning each character
+      frame += `\x1b[${y + 1};1H\x1b[2K${lines[y]?.join('') ?? ''}`
+    }
+    frame += TerminalRendererImpl.encodeCursor(render.cursor)
+    for (const { output } of this.outputs) {
+      output.write(frame)
+    }
+  }
+
+  protected override renderToCells (render: VRenderBatch<VRender>): string[][] {
+    return VRender.collapse(render.layers.map(layer => layer.render)).map(line => line.map(cell => cell.replace(ESCAPE_SEQUENCE, '')))
+  }
+
+  private static encodeFrame (lines: string[][], cursor: CursorPosition | undefined, positionStrictness: PositionStrictness): string {
+    let frame = ''
+    if (positionStrictness === 'strict') {
       // Clear screen and move to top left
-      this.output.write('\x1b[2J')
-      this.output.write('\x1b[H')
+      frame += '\x1b[2J'
+      frame += '\x1b[H'
     }
 
     // Write lines
     lines.forEach((line, i) => {
       line.forEach((char, j) => {
-        if (this.positionStrictness === 'strict') {
+        if (positionStrictness === 'strict') {
           // This moves the cursor to the exact location of the character so there aren't any issues
           // It's expensive but terminal emulation is really varied, especially with images,
           // and there are a lot of terminals which just don't do things the right way
-          this.output.write(`\x1b[${i + 1};${j + 1}H`)
+          frame += `\x1b[${i + 1};${j + 1}H`
         }
-        this.output.write(char)
+        frame += char
       })
 
-      if (this.positionStrictness === 'loose') {
-        this.output.write('\n')
+      if (positionStrictness === 'loose') {
+        frame += '\n'
       }
     })
-    this.linesOutput += lines.length
+
+    // Loose outputs rely on the cursor being at the end of the frame, so they don't get the real cursor
+    if (positionStrictness === 'strict') {
+      frame += TerminalRendererImpl.encodeCursor(cursor)
+    }
+    return frame
+  }
+
+  /** Moves the cursor to its position and shows it, or hides it if there is none */
+  private static encodeCursor (cursor: CursorPosition | undefined): string {
+    if (cursor !== undefined) {
+      return `\x1b[${Math.round(cursor.y) + 1};${Math.round(cursor.x) + 1}H` +
+        `\x1b[${CURSOR_SHAPE_CODES[cursor.shape]} q` +
+        '\x1b[?25h'
+    } else {
+      return '\x1b[?25l'
+    }
   }
 
   protected override getRootDimensions (): {
@@ -131,12 +317,26 @@ export class TerminalRendererImpl extends RendererImpl<VRender, AssetCacher> {
     }
   }
 
+  protected override translateRender (offset: { x: number, y: number }, columnSize: Size, render: VRender): VRender {
+    // Copy because render may be a cached child render, and translating is not idempotent
+    render = render.map(line => [...line])
+    VRender.translate2(render, offset.x, offset.y)
+    return render
+  }
+
+  protected override dimRender (dim: number, render: VRender): VRender {
+    // Copy because render may be a cached child render, and dimming is not idempotent
+    render = render.map(line => [...line])
+    VRender.dim(render, dim)
+    return render
+  }
+
   protected override clipRender (clipRect: Rectangle, columnSize: Size, render: VRender): VRender {
     VRender.clip(clipRect, render)
     return render
   }
 
-  protected override renderText (bounds: BoundingBox, columnSize: Size, wrap: 'word' | 'char' | 'clip' | undefined, color: Color | null, text: string | string[]): VRender {
+  protected override renderText (bounds: BoundingBox, columnSize: Size, wrap: 'word' | 'char' | 'clip' | undefined, color: Color | null, style: Style | null, link: string | null, text: string | string[]): VRender {
     const width = bounds.width ?? Infinity
     const height = bounds.height ?? Infinity
     const input = Array.isArray(text) ? text : text.split('\n')
@@ -146,11 +346,11 @@ export class TerminalRendererImpl extends RendererImpl<VRender, AssetCacher> {
     let nextOutLineWidth = 0
     // eslint-disable-next-line no-labels
     outer: for (const line of input) {
-      const chars = [...line]
+      const chars = Graphemes.split(line)
       let nextWord: string[] = []
       let nextWordWidth = 0
       for (const char of chars) {
-        const charWidth = Strings.width(char)
+        const charWidth = Graphemes.width(char)
         if (wrap === 'word' && /^\w$/.test(char)) {
           // add to word
           // width will never be 0
@@ -205,7 +405,7 @@ export class TerminalRendererImpl extends RendererImpl<VRender, AssetCacher> {
                   // (don't think too hard about it)
                   continue
                 case undefined:
-                  console.warn('text extended past width but wrap is undefined')
+                  Diagnostics.warn('text extended past width but wrap is undefined')
                   break
               }
             }
@@ -231,11 +431,35 @@ export class TerminalRendererImpl extends RendererImpl<VRender, AssetCacher> {
       nextOutLineWidth = 0
     }
 
+    if (link !== null) {
+      // Needs to be before color, since colors are stripped and re-added when collapsing
+      VRender.addLink(result, link)
+    }
+
+    // Style attributes go with the foreground color, so they are opened and closed with it when collapsing
+    let fgOpen = ''
+    let fgClose = ''
+    for (const [attribute, on, off] of STYLE_CODES) {
+      if (style?.[attribute] === true) {
+        fgOpen += `\x1b[${on}m`
+        fgClose += `\x1b[${off}m`
+      }
+    }
     if (color !== null) {
       const rgbColor = Color.toRGB(color)
       const { openEscape, closeEscape } = chalk.rgb(rgbColor.red * 255, rgbColor.green * 255, rgbColor.blue * 255)
-      const fg = CharColor('fg', openEscape, closeEscape)
-      VRender.addColor(result, fg)
+      fgOpen += openEscape
+      fgClose = closeEscape + fgClose
+    }
+    if (fgOpen !== '') {
+      VRender.addColor(result, CharColor('fg', fgOpen, fgClose))
+    }
+
+    if (style?.background !== undefined && style.background !== null) {
+      const bg = TerminalRendererImpl.bgColor(Color(style.background))
+      if (bg !== null) {
+        VRender.addColor(result, bg)
+      }
     }
 
     VRender.translate1(result, bounds)
@@ -243,20 +467,28 @@ export class TerminalRendererImpl extends RendererImpl<VRender, AssetCacher> {
   }
 
   protected override renderSolidColor (rect: Rectangle, columnSize: Size, color: Color): VRender {
-    if (rect.width === 0 || rect.height === 0) {
+    const bg = TerminalRendererImpl.bgColor(color)
+    if (rect.width === 0 || rect.height === 0 || bg === null) {
       return []
     }
 
-    const rgbColor = Color.toRGB(color)
-    const { openEscape, closeEscape } = chalk.bgRgb(rgbColor.red * 255, rgbColor.green * 255, rgbColor.blue * 255)
-    const bg = CharColor('bg', openEscape, closeEscape)
-
     const result: VRender = range(rect.height).map(() => Array(rect.width).fill(` ${bg}`))
 
     VRender.translate2(result, rect.left, rect.top)
     return result
   }
 
+  /** null if `color` is fully transparent. Colors with alpha are blended with the background below when collapsing */
+  private static bgColor (color: Color): CharColor | null {
+    const rgbColor = Color.toRGB(color)
+    const alpha = rgbColor.alpha ?? 1
+    if (alpha <= 0) {
+      return null
+    }
+    const { openEscape, closeEscape } = chalk.bgRgb(rgbColor.red * 255, rgbColor.green * 255, rgbColor.blue * 255)
+    return alpha < 1 ? CharColor.translucentBg(openEscape, closeEscape, alpha) : CharColor('bg', openEscape, closeEscape)
+  }
+
   protected override renderBorder (rect: Rectangle, columnSize: Size, color: Color | null, borderStyle: BorderStyle): VRender {
     if (rect.width === 0 || rect.height === 0) {
       return []
@@ -299,13 +531,15 @@ export class TerminalRendererImpl extends RendererImpl<VRender, AssetCacher> {
   }
 
   protected override renderImage (bounds: BoundingBox, columnSize: Size, src: string, view: VView): { render: VRender, size: Size } {
-    const [image, resolveCallback] = this.assets.getImage(src, bounds.width, bounds.height)
+    // Half-blocks are unrecognizable without colors
+    const format = this.getCapabilities().colors === 2 ? 'ascii' : this.imageFormat
+    const [image, resolveCallback] = this.assets.getImage(src, bounds.width, bounds.height, format)
     if (image === undefined) {
       throw new Error(`Image should not ever be undefined: ${src}`)
     } else if (image === null) {
       resolveCallback(() => this.invalidate(view))
       return {
-        render: this.renderText(bounds, columnSize, 'clip', Color('gray'), '...'),
+        render: this.renderText(bounds, columnSize, 'clip', Color('gray'), null, null, '...'),
         size: { width: '...'.length, height: 1 }
       }
     } else {
@@ -342,22 +576,115 @@ export class TerminalRendererImpl extends RendererImpl<VRender, AssetCacher> {
     }
   }
 
-  override useInput (handler: (key: Key) => void): () => void {
-    function listener (keyStr: string, key: Key): void {
-      if (key.name === undefined) {
-        console.warn(`Unknown key: ${keyStr} ${JSON.stringify(key)}`)
-      } else {
-        handler(key)
+  /** Changes how images are shown, and reloads them */
+  private setImageFormat (format: ImageFormat): void {
+    if (this.imageFormat !== format) {
+      this.imageFormat = format
+      this.assets.clear()
+      this.reroot()
+    }
+  }
+
+  private readonly onData = (data: string): void => {
+    this.sequences.write(data)
+  }
+
+  private onSequences (data: string): void {
+    data = data.replace(DA1_RESPONSE, (_, attributes: string) => {
+      if (attributes.split(';').includes('4') && this.imageFormat === 'fallback') {
+        this.setImageFormat('sixel')
+      }
+      return ''
+    })
+    data = data.replace(KITTY_GRAPHICS_RESPONSE, (_, message: string) => {
+      if (message !== 'OK' && this.imageFormat === 'kitty') {
+        Diagnostics.warn(`terminal rejected kitty image (${message}), falling back to characters`)
+        this.setImageFormat('fallback')
       }
+      return ''
+    })
+    const keys = data.replace(SGR_MOUSE, (_, code: string, x: string, y: string, kind: string) => {
+      const event = decodeMouse(parseInt(code), parseInt(x), parseInt(y), kind === 'm')
+      if (event !== null) {
+        this.sendMouse(event)
+      }
+      return ''
+    })
+    if (keys !== '') {
+      this.keys.write(keys)
     }
-    this.input.addListener('keypress', listener)
-    return () => {
-      this.input.removeListener('keypress', listener)
+  }
+
+  private readonly onKeypress = (keyStr: string, key: Key): void => {
+    // readline recognizes the bracketed paste delimiters
+    if (key.name === 'paste-start') {
+      this.pasteBuffer = ''
+    } else if (key.name === 'paste-end') {
+      if (this.pasteBuffer !== null) {
+        this.sendPaste(this.pasteBuffer)
+        this.pasteBuffer = null
+      }
+    } else if (this.pasteBuffer !== null) {
+      this.pasteBuffer += keyStr
+    } else if (key.name === undefined && keyStr.length === 1 && keyStr >= ' ') {
+      // readline doesn't name punctuation (e.g. '/'), so name it by the character
+      this.sendInput({ ...key, name: keyStr }, 'terminal')
+    } else if (key.name === undefined) {
+      console.warn(`Unknown key: ${keyStr} ${JSON.stringify(key)}`)
+    } else {
+      this.sendInput(key, 'terminal')
     }
   }
 
+  private readonly onResize = (): void => {
+    this.sendResize()
+  }
+
+  protected override getPlatformCapabilities (): Omit<Capabilities, 'fps'> {
+    const colorDepth = typeof this.output.getColorDepth === 'function' ? this.output.getColorDepth() : 24
+    const env = process.env
+    return {
+      images: this.imageFormat === 'fallback' || this.imageFormat === 'ascii' ? 'approximate' : 'native',
+      colors: colorDepth >= 24 ? 'truecolor' : colorDepth >= 8 ? 256 : colorDepth >= 4 ? 16 : 2,
+      mouse: this.mouse,
+      // Loose outputs don't get the real cursor, but the primary output always is strict
+      cursor: this.outputs[0].positionStrictness === 'strict',
+      // Terminals without OSC 8 show the text without the link
+      links: env.TERM_PROGRAM !== 'Apple_Terminal',
+      // OSC 52
+      clipboard: true
+    }
+  }
+
+  override writeClipboard (text: string): void {
+    // OSC 52, goes through the terminal so it works over SSH
+    const osc52 = `\x1b]52;c;${Buffer.from(text).toString('base64')}\x07`
+    for (const { output } of this.outputs) {
+      output.write(osc52)
+    }
+  }
+
+  protected override async flushOutput (): Promise<void> {
+    await Promise.all(this.outputs.map(async ({ output }) => {
+      await new Promise<void>(resolve => output.write('', () => resolve()))
+    }))
+  }
+
   override dispose (): void {
     super.dispose()
+    if (this.bracketedPaste) {
+      this.output.write('\x1b[?2004l')
+    }
+    if (this.mouse) {
+      this.output.write('\x1b[?1002l\x1b[?1006l')
+    }
+    // Restore the default cursor
+    this.output.write('\x1b[0 q\x1b[?25h')
+    this.input.removeListener('data', this.onData)
+    this.sequences.dispose()
+    this.keys.removeListener('keypress', this.onKeypress)
+    this.keys.end()
+    this.output.removeListener('resize', this.onResize)
     this.interact.close()
   }
 }
@@ -0,0 +1,223 @@
+import type { Server, Socket } from 'net'
+import type { ReadStream, WriteStream } from 'tty'
+import type { TerminalRenderOptions } from 'renderer/cli'
+import { EscapeBuffer } from 'renderer/cli/escape-buffer'
+
+/**
+ * Remote rendering: the app runs in one process and a thin viewer in another (e.g. over `ssh`),
+ * connected by a TCP or Unix socket.
+ *
+ * The wire protocol is intentionally dumb so that the viewer can be anything which can pipe a terminal:
+ * - server -> viewer: the exact bytes the renderer would write to a terminal
+ * - viewer -> server: the exact bytes the viewer's terminal inputs,
+ *   interleaved with resize messages `ESC ] devolve-ui;resize;<columns>;<rows> BEL`.
+ *   The viewer always sends a resize message as soon as it connects.
+ */
+export type RemoteAddress = string | number | { host: string, port: number }
+
+const RESIZE_MESSAGE = /\x1b\]devolve-ui;resize;(\d+);(\d+)\x07/g
+const RESIZE_MESSAGE_START = '\x1b]devolve-ui;resize;'
+/** Unfinished resize message, which may be completed by the next chunk */
+const PARTIAL_RESIZE_MESSAGE = /^\x1b\]devolve-ui;resize;\d*(;\d*)?$/
+
+function encodeResize (columns: number, rows: number): string {
+  return `\x1b]devolve-ui;resize;${columns};${rows}\x07`
+}
+
+/**
+ * Calls `onResize` for each resize message in the data written to the result, and `onKeys` with the rest.
+ * Use one per connection, since a message may be split across chunks
+ */
+function viewerDataDecoder (
+  onResize: (columns: number, rows: number) => void,
+  onKeys: (keys: string) => void
+): EscapeBuffer {
+  return new EscapeBuffer(
+    tail => RESIZE_MESSAGE_START.startsWith(tail) || PARTIAL_RESIZE_MESSAGE.test(tail),
+    data => {
+      const keys = data.replace(RESIZE_MESSAGE, (_, columns: string, rows: string) => {
+        onResize(parseInt(columns), parseInt(rows))
+        return ''
+      })
+      if (keys !== '') {
+        onKeys(keys)
+      }
+    }
+  )
+}
+
+export async function listenServer (server: Server, address: RemoteAddress): Promise<void> {
+  await new Promise<void>((resolve, reject) => {
+    server.once('error', reject)
+    if (typeof address === 'object') {
+      server.listen(address.port, address.host, resolve)
+    } else {
+      server.listen(address, resolve)
+    }
+  })
+}
+
+/**
+ * Listens for viewers at `address` (a Unix socket path, port, or host and port).
+ * Every time a viewer connects and sends its dimensions,
+ * `onAttach` is called with `input` and `output` to pass to the renderer (e.g. `new DevolveUI(App, props, { ...opts })`),
+ * and the socket, which you can use to detect when the viewer disconnects.
+ */
+export async function listenTerminal (
+  address: RemoteAddress,
+  onAttach: (opts: Required<Pick<TerminalRenderOptions, 'input' | 'output'>>, socket: Socket) => void
+): Promise<Server> {
+  const net = await import('net')
+  const { PassThrough } = await import('stream')
+
+  const server = net.createServer(socket => {
+    socket.setEncoding('utf8')
+    const input = Object.assign(new PassThrough({ encoding: 'utf8' }), { isTTY: false })
+    // The renderer only writes and reads the dimensions, so the socket itself is the output
+    const output = Object.assign(socket, { isTTY: false, columns: 0, rows: 0 })
+
+    let isAttached = false
+    const decoder = viewerDataDecoder((columns, rows) => {
+      output.columns = columns
+      output.rows = rows
+      if (isAttached) {
+        output.emit('resize')
+      } else {
+        isAttached = true
+        onAttach({
+          input: input as unknown as ReadStream,
+          output: output as unknown as WriteStream
+        }, socket)
+      }
+    }, keys => {
+      input.write(keys)
+    })
+    socket.on('data', (data: string) => decoder.write(data))
+    socket.on('close', () => {
+      decoder.flush()
+      input.end()
+    })
+  })
+
+  await listenServer(server, address)
+  return server
+}
+
+export interface TerminalSession {
+  /** Pass these to the renderer */
+  opts: Required<Pick<TerminalRenderOptions, 'input' | 'output'>>
+  server: Server
+  /** Whether a viewer is currently attached */
+  isAttached: () => boolean
+}
+
+/**
+ * Like {@link listenTerminal}, but for a single long-lived renderer which viewers can detach from and reattach to, like tmux.
+ *
+ * Create the renderer once with `opts`. While no viewer is attached the renderer keeps running headless
+ * and its output is discarded. When a viewer attaches, the renderer is told to resize, which redraws the current frame.
+ * If another viewer attaches, it replaces the current one.
+ *
+ * Use `positionStrictness: 'strict'` (the default) so that every frame is drawn from scratch.
+ */
+export async function listenTerminalSession (address: RemoteAddress): Promise<TerminalSession> {
+  const net = await import('net')
+  const { PassThrough, Writable } = await import('stream')
+
+  let viewer: Socket | null = null
+  const input = Object.assign(new PassThrough({ encoding: 'utf8' }), { isTTY: false })
+  const output = Object.assign(new Writable({
+    write: (chunk, encoding, callback) => {
+      if (viewer !== null) {
+        viewer.write(chunk, encoding, callback)
+      } else {
+        callback()
+      }
+    }
+  }), { isTTY: false, columns: 80, rows: 24 })
+
+  const server = net.createServer(socket => {
+    socket.setEncoding('utf8')
+    let isAttached = false
+    const decoder = viewerDataDecoder((columns, rows) => {
+      if (!isAttached) {
+        isAttached = true
+        viewer?.end()
+        viewer = socket
+      }
+      output.columns = columns
+      output.rows = rows
+      output.emit('resize')
+    }, keys => {
+      if (isAttached) {
+        input.write(keys)
+      }
+    })
+    socket.on('data', (data: string) => decoder.write(data))
+    socket.on('close', () => {
+      decoder.dispose()
+      if (viewer === socket) {
+        viewer = null
+      }
+    })
+  })
+
+  await listenServer(server, address)
+  return {
+    opts: {
+      input: input as unknown as ReadStream,
+      output: output as unknown as WriteStream
+    },
+    server,
+    isAttached: () => viewer !== null
+  }
+}
+
+/**
+ * Connects this process's terminal to a renderer listening at `address` (see {@link listenTerminal}).
+ * Resolves when the server closes the connection.
+ */
+export async function attachTerminal (
+  address: RemoteAddress,
+  input: ReadStream = process.stdin,
+  output: WriteStream = process.stdout
+): Promise<void> {
+  const net = await import('net')
+
+  const socket = typeof address === 'object'
+    ? net.createConnection(address.port, address.host)
+    : typeof address === 'number'
+      ? net.createConnection(address)
+      // Separate branch because createConnection has separate overloads for ports and paths
+      : net.createConnection(address)
+  await new Promise<void>((resolve, reject) => {
+    socket.once('error', reject)
+    socket.once('connect', resolve)
+  })
+
+  const sendResize = (): void => {
+    socket.write(encodeResize(output.columns, output.rows))
+  }
+  sendResize()
+
+  const wasRaw = input.isTTY ? input.isRaw : false
+  if (input.isTTY) {
+    input.setRawMode(true)
+  }
+  input.pipe(socket)
+  socket.pipe(output)
+  output.on('resize', sendResize)
+
+  await new Promise<void>(resolve => {
+    socket.once('close', () => {
+      input.unpipe(socket)
+      socket.unpipe(output)
+      output.removeListener('resize', sendResize)
+      if (input.isTTY) {
+        input.setRawMode(wasRaw)
+      }
+      input.pause()
+      resolve()
+    })
+  })
+}
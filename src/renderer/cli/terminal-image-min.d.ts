@@ -2,12 +2,27 @@ import { VRender } from 'renderer/cli/VRender'
 
 export declare type Percent = `${number}%`
 export declare type Dimension = number | Percent | undefined
+/**
+ * - `'iterm'`, `'kitty'`, `'sixel'`: real pixels using the terminal's image protocol
+ * - `'fallback'`: half-block characters with foreground and background colors, 2 pixels per cell
+ * - `'ascii'`: characters chosen by luminance, colored if the terminal supports it. For terminals with few or no colors
+ */
+export declare type ImageFormat = 'iterm' | 'kitty' | 'sixel' | 'fallback' | 'ascii'
 export interface ImageOptions {
   width?: Dimension
   height?: Dimension
   preserveAspectRatio?: boolean
+  /** Default is detected from the terminal: a protocol if supported, otherwise `'fallback'` */
+  format?: ImageFormat
 }
 
+/**
+ * The best image format the terminal supports, detected from environment variables.
+ * Set `DEVOLVE_UI_IMAGES` to one of the formats to override.
+ * Never `'ascii'` unless overridden, since that depends on colors
+ */
+export declare function getImageSupport (): ImageFormat
+
 export declare const terminalImage: {
   /**
    Display images in the terminal.
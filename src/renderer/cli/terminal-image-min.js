@@ -7,6 +7,8 @@ import * as Sixel from 'sixel'
 import { CharColor, TRANSPARENT } from 'renderer/cli/CharColor'
 
 const PIXEL = '\u2584'
+// From darkest to lightest
+const ASCII_RAMP = ' .:-=+*#%@'
 const IS_NODE = typeof window === 'undefined'
 
 // See https://saitoha.github.io/libsixel#terminal-requirements
@@ -27,16 +29,26 @@ const SIXEL_TERMINALS = [
   'cancer'
 ]
 
-function getImageSupport () {
+const IMAGE_FORMATS = ['iterm', 'kitty', 'sixel', 'fallback', 'ascii']
+
+export function getImageSupport () {
   if (!IS_NODE) {
     return 'fallback'
   }
 
+  const override = process.env.DEVOLVE_UI_IMAGES?.toLowerCase()
+  if (override !== undefined && IMAGE_FORMATS.includes(override)) {
+    return override
+  }
+
   const terminal = (process.env.LC_TERMINAL ?? process.env.TERM_PROGRAM ?? '').toLowerCase()
   const terminalVersion = process.env.LC_TERMINAL_VERSION ?? process.env.TERM_PROGRAM_VERSION ?? ''
   if (terminal.startsWith('iterm') && terminalVersion.startsWith('3')) {
     return 'iterm'
-  } else if (terminal.startsWith('kitty')) {
+  } else if (terminal.startsWith('wezterm')) {
+    // WezTerm supports the iTerm2 protocol
+    return 'iterm'
+  } else if (terminal.startsWith('kitty') || process.env.KITTY_WINDOW_ID !== undefined || process.env.TERM === 'xterm-kitty') {
     return 'kitty'
   } else if (SIXEL_TERMINALS.some(prefix => terminal.startsWith(prefix))) {
     return 'sixel'
@@ -176,11 +188,38 @@ function renderFallback (image, imageData, { width, height }) {
   return result
 }
 
+function renderAscii (image, imageData, { width, height }) {
+  const ratio = {
+    width: image.width / width,
+    height: image.height / height
+  }
+  const result = []
+  for (let y1 = 0; y1 < height; y1++) {
+    const y2 = Math.floor(y1 * ratio.height)
+    const line = []
+    for (let x1 = 0; x1 < width; x1++) {
+      const x2 = Math.floor(x1 * ratio.width)
+      const { r, g, b, a } = getRGBA(imageData[y2 * image.width + x2])
+      if (a === 0) {
+        line.push(TRANSPARENT)
+      } else {
+        // Relative luminance, premultiplied by alpha
+        const luminance = (0.2126 * r + 0.7152 * g + 0.0722 * b) / 255 * (a / 255)
+        const char = ASCII_RAMP[Math.round(luminance * (ASCII_RAMP.length - 1))]
+        const { openEscape: fgOpen, closeEscape: fgClose } = chalk.rgb(r, g, b)
+        line.push(char + CharColor('fg', fgOpen, fgClose))
+      }
+    }
+    result.push(line)
+  }
+  return result
+}
+
 function render (buffer, options) {
   const image = UPNG.decode(buffer)
   const imageData = UPNG.toRGBA8(image)[0]
   const size = calculateScaledWidthHeight(image.width, image.height, options)
-  switch (getImageSupport()) {
+  switch (options.format ?? getImageSupport()) {
     case 'sixel':
       return renderSixel(image, new Uint8Array(imageData), size)
     case 'kitty':
@@ -189,6 +228,8 @@ function render (buffer, options) {
       return renderIterm(buffer, size)
     case 'fallback':
       return renderFallback(image, new Uint32Array(imageData), size)
+    case 'ascii':
+      return renderAscii(image, new Uint32Array(imageData), size)
   }
 }
 
@@ -1,12 +1,26 @@
-import { BoundingBox, Bounds, Color, DelayedSubLayout, ParentBounds, Rectangle, Size, VView, VNode } from 'core/view'
-import { CoreRenderOptions, DEFAULT_CORE_RENDER_OPTIONS, DEFAULT_COLUMN_SIZE, Renderer } from 'core/renderer'
-import { doLogRender, VComponent, VRoot } from 'core/component'
-import { assert, Key, Strings } from '@raycenity/misc-ts'
+import { BoundingBox, Bounds, Color, ColorSpec, CursorShape, DelayedSubLayout, Measurement, ParentBounds, Rectangle, Size, VView, VNode } from 'core/view'
+import { CoreRenderOptions, DEFAULT_CORE_RENDER_OPTIONS, DEFAULT_COLUMN_SIZE, Capabilities, FrameStats, InputHandler, InputSource, MouseInput, Renderer, ShutdownSummary } from 'core/renderer'
+import { doLogRender, getMinContrastRatio, getRenderLogSink, getVComponentPath, isDebugMode, VComponent, VRoot } from 'core/component'
+import { assert, Key } from '@raycenity/misc-ts'
 import { BorderStyle } from 'core/view/border-style'
+import { Style } from 'core/view/style'
+import { RenderLogEntry } from 'core/render-log'
+import { Diagnostics } from 'core/diagnostics'
+import { Graphemes } from 'core/graphemes'
+import { Query, QueryMatch, QueryPredicate } from 'core/query'
+import { Accessibility, AccessibilityTree } from 'core/accessibility'
 import type { DisplayObject } from 'pixi.js'
+import type { FocusHandle } from 'core/hooks/focus'
 
 type Timer = NodeJS.Timer
 
+/** Consecutive overrunning frames before lowering the FPS, and fast frames before raising it back */
+const ADAPTIVE_FPS_OVERRUNS = 3
+const ADAPTIVE_FPS_RECOVERY_FRAMES = 30
+
+/** Above everything the app renders */
+const LAYOUT_DEBUG_Z_INDEX = Number.MAX_SAFE_INTEGER
+
 export abstract class CoreAssetCacher {
   private readonly assets: Map<string, any> = new Map()
 
@@ -20,6 +34,11 @@ export abstract class CoreAssetCacher {
     }
   }
 
+  /** Forgets every asset, e.g. if they need to be loaded differently */
+  clear (): void {
+    this.assets.clear()
+  }
+
   protected getAsync<T>(path: string, construct: (path: string) => Promise<T>): [T | null, (didFind: () => void) => void] {
     if (this.assets.has(path)) {
       return [this.assets.get(path), () => {}]
@@ -36,18 +55,89 @@ export abstract class CoreAssetCacher {
 }
 
 export interface VRenderBatch<VRender> {
-  [zPosition: number]: VRender
+  /** Topmost first, see {@link VRenderLayer.compare} */
+  layers: Array<VRenderLayer<VRender>>
   rect: Rectangle | null
+  /** Where the real cursor should be, if any view requested it */
+  cursor?: CursorPosition
+  /** Views with `onClick` or `onWheel`, in no particular order */
+  clickTargets?: ClickTarget[]
+}
+
+export interface VRenderLayer<VRender> {
+  render: VRender
+  /** The view's resolved `z` */
+  z: number
+  /** The `zIndex` of each of the view's ancestors, outermost first, and the view (0 if not set) */
+  zIndices: number[]
+}
+
+export module VRenderLayer {
+  export function of<VRender> (z: number, render: VRender): VRenderLayer<VRender> {
+    return { render, z, zIndices: [] }
+  }
+
+  /**
+   * Negative if `lhs` is above `rhs`: `zIndices` are compared first (outermost first, so a view's `zIndex` applies to its whole subtree),
+   * then `z`. Sorting is stable, so layers which are equal keep their document order
+   */
+  export function compare (lhs: Pick<VRenderLayer<unknown>, 'z' | 'zIndices'>, rhs: Pick<VRenderLayer<unknown>, 'z' | 'zIndices'>): number {
+    const length = Math.max(lhs.zIndices.length, rhs.zIndices.length)
+    for (let i = 0; i < length; i++) {
+      const difference = (rhs.zIndices[i] ?? 0) - (lhs.zIndices[i] ?? 0)
+      if (difference !== 0) {
+        return difference
+      }
+    }
+    return rhs.z - lhs.z
+  }
+}
+
+export interface ClickTarget {
+  rect: Rectangle
+  z: number
+  /** See {@link VRenderLayer.zIndices} */
+  zIndices: number[]
+  onClick?: (event: MouseInput) => void
+  onWheel?: (event: MouseInput) => void
+  /** Focused when clicked, see {@link CommonAttrs.focus} */
+  focus?: FocusHandle
+}
+
+export interface CursorPosition {
+  x: number
+  y: number
+  shape: CursorShape
+}
+
+/** A mouse move or resize waiting to be sent, see {@link CoreRenderOptions.coalesceInput} */
+type CoalescedInput = { type: 'move', event: MouseInput } | { type: 'resize' }
+
+interface RenderLogContext {
+  path: string
+  hash: string
+  cache: RenderLogEntry['cache']
+  bounds: BoundingBox | null
 }
 
 interface CachedRenderInfo {
   parentBounds: ParentBounds
   siblingBounds: Rectangle | null
+  /** null if invisible */
+  bounds: BoundingBox | null
   parent: number
+  /** See {@link RendererImpl.renderHash}, only set when logging renders */
+  hash: string | null
 }
 
 export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher> implements Renderer {
   private readonly defaultFps: number
+  /** Random number in [0, 1), from the `random` render option */
+  readonly random: () => number
+  /** Current time in milliseconds, from the `now` render option */
+  readonly now: () => number
+  /** Milliseconds between keys of a multi-key binding, from the `chordTimeout` render option */
+  readonly chordTimeout: number
   root: VComponent | null = null
   protected readonly assets: AssetCacher
 
@@ -55,9 +145,49 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
   private needsRerender: boolean = false
   private timer: Timer | null = null
   private isVisible: boolean = false
+  private readonly inputHandlers: Set<InputHandler> = new Set()
+  /** Only the last gets input, see {@link captureInput} */
+  private readonly inputCaptures: InputHandler[] = []
+  private readonly inputSourceDestructors: Set<() => void> = new Set()
+  private readonly pasteHandlers: Set<(text: string) => void> = new Set()
+  private readonly mouseHandlers: Set<(event: MouseInput) => void> = new Set()
+  private lastInputTime: number
+  private readonly coalesceInput: boolean
+  /** Moves and resizes received this tick, at most one of each in a row */
+  private pendingCoalescedInput: CoalescedInput[] = []
+  private coalesceTimer: ReturnType<typeof setTimeout> | null = null
+  private layoutDebug: boolean
+  private readonly adaptiveFps: boolean
+  private frameStats: FrameStats
+  /** Consecutive overrunning frames, or negative for consecutive fast frames, for adaptive FPS */
+  private frameStreak: number = 0
+  private readonly exitBlockers: Set<() => boolean | Promise<boolean>> = new Set()
+  /** Handlers registered while a component was updating which haven't been removed, and the component's path, see {@link trackComponentHandler} */
+  private readonly componentHandlers: Map<object, string> = new Map()
+  /** Set when disposed, see {@link releaseLeakedHandlers} */
+  private leakedHandlers: number = 0
+  /** The last render written, used for hit testing */
+  private lastRender: VRenderBatch<VRender> | null = null
+  /** Rects which changed since the last render was written, or null if everything may have, see {@link writeRenderDiff} */
+  private dirtyRects: Rectangle[] | null = null
+  /** Incremented on every rerender, so waiters can't miss one which happens between checking and waiting */
+  private generation: number = 0
+  /** Paths of the views currently being rendered, only tracked when logging renders */
+  private readonly renderPaths: string[] = []
+  /** Time spent rendering children of the views currently being rendered, only tracked when logging renders */
+  private readonly renderChildDurations: number[] = []
+  private rerenderWaiters: Array<{ afterGeneration: number, resolve: (generation: number) => void, reject: (error: Error) => void }> = []
 
-  protected constructor (assetCacher: AssetCacher, { fps }: CoreRenderOptions) {
+  protected constructor (assetCacher: AssetCacher, { fps, random, now, adaptiveFps, layoutDebug, coalesceInput, chordTimeout }: CoreRenderOptions) {
     this.defaultFps = fps ?? DEFAULT_CORE_RENDER_OPTIONS.fps
+    this.random = random ?? DEFAULT_CORE_RENDER_OPTIONS.random
+    this.now = now ?? DEFAULT_CORE_RENDER_OPTIONS.now
+    this.chordTimeout = chordTimeout ?? DEFAULT_CORE_RENDER_OPTIONS.chordTimeout
+    this.lastInputTime = this.now()
+    this.layoutDebug = layoutDebug ?? DEFAULT_CORE_RENDER_OPTIONS.layoutDebug
+    this.adaptiveFps = adaptiveFps ?? DEFAULT_CORE_RENDER_OPTIONS.adaptiveFps
+    this.coalesceInput = coalesceInput ?? DEFAULT_CORE_RENDER_OPTIONS.coalesceInput
+    this.frameStats = RendererImpl.emptyFrameStats(this.defaultFps)
     this.assets = assetCacher
   }
 
@@ -72,11 +202,69 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
       throw new Error('Renderer is already running')
     }
 
+    fps = fps ?? this.defaultFps
+    this.frameStats.targetFps = fps
+    this.frameStats.currentFps = fps
+    this.frameStreak = 0
+    this.startTimer(fps)
+  }
+
+  private startTimer (fps: number): void {
+    const intervalMs = 1000 / fps
     this.timer = setInterval(() => {
       if (this.needsRerender && this.isVisible) {
+        const start = performance.now()
         this.forceRerender()
+        this.recordFrame(performance.now() - start, intervalMs)
       }
-    }, 1 / (fps ?? this.defaultFps))
+    }, intervalMs)
+  }
+
+  private recordFrame (durationMs: number, intervalMs: number): void {
+    const stats = this.frameStats
+    stats.frames++
+    stats.lastFrameMs = durationMs
+    stats.averageFrameMs += (durationMs - stats.averageFrameMs) / stats.frames
+    stats.maxFrameMs = Math.max(stats.maxFrameMs, durationMs)
+    if (durationMs > intervalMs) {
+      stats.overruns++
+      stats.droppedFrames += Math.floor(durationMs / intervalMs)
+      this.frameStreak = Math.max(0, this.frameStreak) + 1
+    } else if (durationMs < intervalMs / 2) {
+      this.frameStreak = Math.min(0, this.frameStreak) - 1
+    } else {
+      this.frameStreak = 0
+    }
+
+    if (!this.adaptiveFps || this.timer === null) {
+      return
+    }
+    let newFps = stats.currentFps
+    if (this.frameStreak >= ADAPTIVE_FPS_OVERRUNS) {
+      newFps = Math.max(1, Math.floor(stats.currentFps / 2))
+    } else if (-this.frameStreak >= ADAPTIVE_FPS_RECOVERY_FRAMES) {
+      newFps = Math.min(stats.targetFps, stats.currentFps * 2)
+    }
+    if (newFps !== stats.currentFps) {
+      stats.currentFps = newFps
+      this.frameStreak = 0
+      clearInterval(this.timer)
+      this.startTimer(newFps)
+    }
+  }
+
+  /** Frame timing and overruns since the renderer started or {@link resetFrameStats} was called */
+  getFrameStats (): FrameStats {
+    return { ...this.frameStats }
+  }
+
+  resetFrameStats (): void {
+    const { targetFps, currentFps } = this.frameStats
+    this.frameStats = { ...RendererImpl.emptyFrameStats(targetFps), currentFps }
+  }
+
+  private static emptyFrameStats (fps: number): FrameStats {
+    return { frames: 0, overruns: 0, droppedFrames: 0, lastFrameMs: 0, averageFrameMs: 0, maxFrameMs: 0, targetFps: fps, currentFps: fps }
   }
 
   stop (): void {
@@ -98,6 +286,7 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
     this.stop()
     this.clear()
     this.isVisible = false
+    this.dirtyRects = null
   }
 
   invalidate (node: VNode): void {
@@ -108,9 +297,14 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
     while (nextViewId !== -1) {
       if (this.cachedRenders.has(nextViewId)) {
         const viewId = nextViewId
-        nextViewId = this.cachedRenders.get(viewId)!.parent
+        const cachedRender = this.cachedRenders.get(viewId)!
+        nextViewId = cachedRender.parent
         this.cachedRenders.delete(viewId)
-        RendererImpl.logRender('- found ->', nextViewId)
+        // Ancestors are re-rendered to merge the new render, but only this view's area changes
+        if (viewId === view.id) {
+          this.markDirty(cachedRender.rect)
+        }
+        RendererImpl.logRender('- found', cachedRender.hash, '->', nextViewId)
       } else {
         RendererImpl.logRender('- not found')
         break
@@ -119,6 +313,15 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
     this.needsRerender = true
   }
 
+  /**
+   * Call when the root dimensions change.
+   * We don't clear the cache: views whose resolved bounds don't change (e.g. fixed-size subtrees) keep their renders
+   */
+  protected invalidateRoot (): void {
+    this.needsRerender = true
+    this.dirtyRects = null
+  }
+
   reroot<Props> (props?: Props, mkRoot?: (props: Props) => VView): void {
     if (props !== undefined) {
       this.root!.props = props
@@ -129,26 +332,293 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
     VComponent.update(this.root!, mkRoot !== undefined ? 'set-root' : props !== undefined ? 'set-props' : 'manual')
     this.cachedRenders.clear()
     this.needsRerender = true
+    this.dirtyRects = null
+  }
+
+  /**
+   * Replaces the root component's function with `mkRoot` (e.g. from re-importing the app's module) and updates the tree,
+   * keeping components' state, so the UI can be changed without restarting and losing navigation or input state.
+   * See {@link VComponent.hotReload} for when state is kept
+   */
+  hotReload<Props> (mkRoot: (props: Props) => VNode): void {
+    VComponent.hotReload(this.root! as VComponent<Props>, mkRoot)
+    this.cachedRenders.clear()
+    this.needsRerender = true
+    this.dirtyRects = null
   }
 
   forceRerender (): void {
     this.needsRerender = false
-    this.clear()
     assert(this.root!.node !== null, 'sanity check failed: root not created by the time forceRender is called')
-    this.writeRender(this.renderNode(null, this.getRootParentBounds(), null, this.root!.node))
+    let render = this.renderNode(null, this.getRootParentBounds(), null, this.root!.node)
+    if (this.layoutDebug) {
+      render = this.addLayoutDebugOverlay(render)
+    }
+    // The layout debug overlay covers everything, so it's always redrawn entirely
+    const dirtyRects = this.layoutDebug ? null : this.dirtyRects
+    this.dirtyRects = []
+    this.lastRender = render
+    if (dirtyRects !== null && this.writeRenderDiff !== undefined) {
+      this.writeRenderDiff(render, RendererImpl.simplifyDirtyRects(dirtyRects))
+    } else {
+      this.clear()
+      this.writeRender(render)
+    }
+
+    this.generation++
+    const waiters = this.rerenderWaiters
+    this.rerenderWaiters = []
+    for (const waiter of waiters) {
+      if (waiter.afterGeneration < this.generation) {
+        waiter.resolve(this.generation)
+      } else {
+        this.rerenderWaiters.push(waiter)
+      }
+    }
+  }
+
+  /** The number of times the renderer has rerendered */
+  getGeneration (): number {
+    return this.generation
+  }
+
+  /**
+   * Resolves with the new generation once the renderer rerenders after `afterGeneration` (default: the current generation).
+   * Get the generation before making a change, then pass it here to wait for the change to be shown, even if it already has.
+   * Rejects if the renderer is disposed first.
+   */
+  async waitForRerender (afterGeneration: number = this.generation): Promise<number> {
+    if (afterGeneration < this.generation) {
+      return this.generation
+    }
+    return await new Promise((resolve, reject) => {
+      this.rerenderWaiters.push({ afterGeneration, resolve, reject })
+    })
+  }
+
+  /**
+   * If a component is updating (e.g. running an effect), remembers that it registered `handler`,
+   * so if the handler is still registered when the renderer is disposed, it's reported as leaked
+   */
+  private trackComponentHandler (handler: object): () => void {
+    const componentPath = getVComponentPath()
+    if (componentPath === '') {
+      return () => {}
+    }
+    this.componentHandlers.set(handler, componentPath)
+    return () => {
+      this.componentHandlers.delete(handler)
+    }
+  }
+
+  /** Calls `handler` on every input event from every source. Returns a function which removes the handler */
+  useInput (handler: InputHandler): () => void {
+    this.inputHandlers.add(handler)
+    const untrack = this.trackComponentHandler(handler)
+    return () => {
+      this.inputHandlers.delete(handler)
+      untrack()
+    }
+  }
+
+  /**
+   * While the returned function isn't called, sends input only to `handler` instead of every input handler,
+   * e.g. for a mode which takes over the keyboard. If there are multiple captures, the latest gets input
+   */
+  captureInput (handler: InputHandler): () => void {
+    this.inputCaptures.push(handler)
+    const untrack = this.trackComponentHandler(handler)
+    return () => {
+      const index = this.inputCaptures.lastIndexOf(handler)
+      if (index !== -1) {
+        this.inputCaptures.splice(index, 1)
+      }
+      untrack()
+    }
   }
 
-  abstract useInput (handler: (key: Key) => void): () => void
+  /** Timestamp ({@link now}) of the last key, mouse or paste input, or when the renderer was created if there was none */
+  getLastInputTime (): number {
+    return this.lastInputTime
+  }
+
+  /**
+   * Sends `key` to every input handler as if it came from `source`, e.g. to inject events from tests or automation.
+   * Goes through the same path as platform input, so if input is captured (see {@link captureInput}) only the capture gets it
+   */
+  sendInput (key: Key, source: InputSource = 'synthetic'): void {
+    this.flushCoalescedInput()
+    this.lastInputTime = this.now()
+    if (this.inputCaptures.length > 0) {
+      this.inputCaptures[this.inputCaptures.length - 1](key, source)
+      return
+    }
+    for (const handler of [...this.inputHandlers]) {
+      handler(key, source)
+    }
+  }
+
+  /**
+   * Merges another input source (e.g. a channel of synthetic events or a gamepad) into the renderer's input,
+   * alongside the platform's input. `subscribe` is called immediately with a function to send events,
+   * and returns a function to unsubscribe, which is called when the returned function is called or the renderer is disposed.
+   */
+  addInputSource (source: InputSource, subscribe: (send: (key: Key) => void) => () => void): () => void {
+    const unsubscribe = subscribe(key => this.sendInput(key, source))
+    const destructor = (): void => {
+      if (this.inputSourceDestructors.delete(destructor)) {
+        unsubscribe()
+      }
+    }
+    this.inputSourceDestructors.add(destructor)
+    return destructor
+  }
+
+  /** Calls `handler` with text the user pastes. Returns a function which removes the handler */
+  usePaste (handler: (text: string) => void): () => void {
+    this.pasteHandlers.add(handler)
+    const untrack = this.trackComponentHandler(handler)
+    return () => {
+      this.pasteHandlers.delete(handler)
+      untrack()
+    }
+  }
+
+  /** Calls `handler` on every mouse event. Returns a function which removes the handler */
+  useMouse (handler: (event: MouseInput) => void): () => void {
+    this.mouseHandlers.add(handler)
+    const untrack = this.trackComponentHandler(handler)
+    return () => {
+      this.mouseHandlers.delete(handler)
+      untrack()
+    }
+  }
+
+  /**
+   * Sends `event` to every mouse handler, and if it's a press (or wheel),
+   * to the `onClick` (or `onWheel`) of the topmost view under the mouse which has one.
+   * Moves are sent on the next tick, and only the latest of consecutive moves, unless `coalesceInput` is false
+   */
+  sendMouse (event: MouseInput): void {
+    if (event.type === 'move' && this.coalesceInput) {
+      this.queueCoalescedInput({ type: 'move', event })
+      return
+    }
+    this.flushCoalescedInput()
+    this.dispatchMouse(event)
+  }
+
+  private dispatchMouse (event: MouseInput): void {
+    this.lastInputTime = this.now()
+    for (const handler of [...this.mouseHandlers]) {
+      handler(event)
+    }
+    const isWheel = event.type === 'wheel-up' || event.type === 'wheel-down'
+    if ((event.type === 'press' || isWheel) && this.lastRender?.clickTargets !== undefined) {
+      const getHandler = (target: ClickTarget): ((event: MouseInput) => void) | undefined => {
+        if (isWheel) {
+          return target.onWheel
+        }
+        const { focus, onClick } = target
+        return focus === undefined
+          ? onClick
+          : event => {
+              focus.requestFocus()
+              onClick?.(event)
+            }
+      }
+      let topTarget: ClickTarget | null = null
+      for (const target of this.lastRender.clickTargets) {
+        // Targets are in document order, so earlier ones win ties like they do when rendering
+        if (getHandler(target) !== undefined && Rectangle.contains(target.rect, event) && (topTarget === null || VRenderLayer.compare(target, topTarget) < 0)) {
+          topTarget = target
+        }
+      }
+      if (topTarget !== null) {
+        getHandler(topTarget)?.(event)
+      }
+    }
+  }
+
+  /**
+   * Call when the root dimensions change, instead of {@link invalidateRoot},
+   * so a burst of resizes only causes one relayout
+   */
+  protected sendResize (): void {
+    if (this.coalesceInput) {
+      this.queueCoalescedInput({ type: 'resize' })
+    } else {
+      this.invalidateRoot()
+    }
+  }
+
+  private queueCoalescedInput (input: CoalescedInput): void {
+    const last = this.pendingCoalescedInput[this.pendingCoalescedInput.length - 1]
+    if (last?.type === input.type) {
+      this.pendingCoalescedInput[this.pendingCoalescedInput.length - 1] = input
+    } else {
+      this.pendingCoalescedInput.push(input)
+    }
+    if (this.coalesceTimer === null) {
+      // A timeout and not a microtask, so input split across multiple reads is still coalesced
+      this.coalesceTimer = setTimeout(() => this.flushCoalescedInput(), 0)
+    }
+  }
+
+  /** Sends pending moves and resizes, so they are handled before input which came after them */
+  private flushCoalescedInput (): void {
+    if (this.coalesceTimer !== null) {
+      clearTimeout(this.coalesceTimer)
+      this.coalesceTimer = null
+    }
+    const pending = this.pendingCoalescedInput
+    this.pendingCoalescedInput = []
+    for (const input of pending) {
+      switch (input.type) {
+        case 'move':
+          this.dispatchMouse(input.event)
+          break
+        case 'resize':
+          this.invalidateRoot()
+          break
+      }
+    }
+  }
+
+  /**
+   * Sends `text` to every paste handler as if it was pasted, e.g. to inject pastes from tests or automation.
+   * Like platform pastes, sends pending moves and resizes first
+   */
+  sendPaste (text: string): void {
+    this.flushCoalescedInput()
+    this.lastInputTime = this.now()
+    for (const handler of [...this.pasteHandlers]) {
+      handler(text)
+    }
+  }
+
+  /** Copies `text` to the system clipboard. In the terminal this works over SSH if the terminal supports OSC 52 */
+  abstract writeClipboard (text: string): void
 
   protected abstract clear (): void
   protected abstract writeRender (render: VRenderBatch<VRender>): void
+  /**
+   * Optional, for platforms which can update part of the screen: instead of clearing and calling {@link writeRender},
+   * called with the full render and the rects (in columns and rows, unordered, possibly overlapping) which changed since the last render.
+   * Everything outside of them is the same as the last render. Not called for the first render, or after a resize or cache clear
+   */
+  protected writeRenderDiff?(render: VRenderBatch<VRender>, dirtyRects: Rectangle[]): void
   protected abstract getRootDimensions (): {
     boundingBox: BoundingBox
     columnSize?: Size
   }
+  /** Can mutate `render` if it's faster. `offset` is in columns and rows, and integer */
+  protected abstract translateRender (offset: { x: number, y: number }, columnSize: Size, render: VRender): VRender
+  /** Can mutate `render` if it's faster. Darkens all colors by `dim` (0 = unchanged, 1 = black) */
+  protected abstract dimRender (dim: number, render: VRender): VRender
   /** Can mutate `render` if it's faster */
   protected abstract clipRender (clipRect: Rectangle, columnSize: Size, render: VRender): VRender
-  protected abstract renderText (bounds: BoundingBox, columnSize: Size, wrapMode: 'word' | 'char' | 'clip' | undefined, color: Color | null, text: string, node: VView): VRender
+  protected abstract renderText (bounds: BoundingBox, columnSize: Size, wrapMode: 'word' | 'char' | 'clip' | undefined, color: Color | null, style: Style | null, link: string | null, text: string, node: VView): VRender
   protected abstract renderSolidColor (rect: Rectangle, columnSize: Size, color: Color, node: VView): VRender
   protected abstract renderBorder (rect: Rectangle, columnSize: Size, color: Color | null, borderStyle: BorderStyle, node: VView): VRender
   protected abstract renderImage (bounds: BoundingBox, columnSize: Size, src: string, node: VView): { render: VRender, size: Size }
@@ -156,78 +626,420 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
   protected abstract renderPixi (bounds: BoundingBox, columnSize: Size, pixi: DisplayObject | 'terminal', getSize: ((pixi: DisplayObject, bounds: BoundingBox, columnSize: Size) => Size) | undefined, node: VView): { render: VRender, size: Size | null }
 
   protected renderNode (parent: VView | null, parentBounds: ParentBounds, siblingBounds: Rectangle | null, node: VNode): VRenderBatch<VRender> {
+    const renderLogSink = getRenderLogSink()
+    if (!doLogRender() && renderLogSink === null) {
+      return this.renderNodeCached(parent, parentBounds, siblingBounds, node, null)
+    }
+
+    const view = VNode.view(node)
+    const path = RendererImpl.renderPath(this.renderPaths[this.renderPaths.length - 1] ?? '', parent, node)
+    const log: RenderLogContext = { path, hash: RendererImpl.renderHash(path, view), cache: 'miss', bounds: null }
+    this.renderPaths.push(path)
+    this.renderChildDurations.push(0)
+    const start = performance.now()
+    try {
+      const render = this.renderNodeCached(parent, parentBounds, siblingBounds, node, log)
+      const durationMs = performance.now() - start
+      const childDurationMs = this.renderChildDurations[this.renderChildDurations.length - 1]
+      this.renderChildDurations.pop()
+      if (this.renderChildDurations.length > 0) {
+        this.renderChildDurations[this.renderChildDurations.length - 1] += durationMs
+      }
+      renderLogSink?.({
+        frame: this.generation,
+        path,
+        viewId: view.id,
+        hash: log.hash,
+        cache: log.cache,
+        bounds: log.bounds,
+        rect: render.rect,
+        durationMs,
+        selfDurationMs: durationMs - childDurationMs
+      })
+      return render
+    } finally {
+      this.renderPaths.pop()
+      if (this.renderChildDurations.length > this.renderPaths.length) {
+        // Rendering threw
+        this.renderChildDurations.pop()
+      }
+    }
+  }
+
+  /** `log` is only provided when logging renders */
+  private renderNodeCached (parent: VView | null, parentBounds: ParentBounds, siblingBounds: Rectangle | null, node: VNode, log: RenderLogContext | null): VRenderBatch<VRender> {
     const view = VNode.view(node)
 
-    RendererImpl.logRender('render', view, 'parent', parent)
+    RendererImpl.logRender('render', log?.hash, log?.path, view, 'parent', parent)
+    const bounds = view.visible === false ? null : (view.bounds ?? Bounds.DEFAULT)(parentBounds, siblingBounds)
+    if (log !== null) {
+      log.bounds = bounds
+    }
     if (this.cachedRenders.has(view.id)) {
-      RendererImpl.logRender('- cached')
       const cachedRender = this.cachedRenders.get(view.id)!
+      RendererImpl.logRender('- cached', cachedRender.hash)
       if (
         ParentBounds.equals(cachedRender.parentBounds, parentBounds) &&
         Rectangle.equals(cachedRender.siblingBounds, siblingBounds)
       ) {
+        if (log !== null) {
+          log.cache = 'hit'
+        }
+        return cachedRender
+      } else if (
+        bounds !== null &&
+        cachedRender.bounds !== null &&
+        BoundingBox.equals(cachedRender.bounds, bounds) &&
+        Size.equals(cachedRender.parentBounds.columnSize, parentBounds.columnSize) &&
+        (Size.equals(cachedRender.parentBounds.viewport, parentBounds.viewport) || !RendererImpl.usesViewport(view)) &&
+        RendererImpl.dependsOnlyOnBounds(view, bounds)
+      ) {
+        // The parent or sibling changed (e.g. the root was resized), but not in a way which affects this view
+        RendererImpl.logRender('- same bounds')
+        if (log !== null) {
+          log.cache = 'same-bounds'
+        }
+        cachedRender.parentBounds = parentBounds
+        cachedRender.siblingBounds = siblingBounds
+        cachedRender.parent = parent?.id ?? -1
         return cachedRender
       } else {
         this.cachedRenders.delete(view.id)
+        if (!RendererImpl.isContainer(view)) {
+          this.markDirty(cachedRender.rect)
+        }
       }
     }
-    const render: VRenderBatch<VRender> & CachedRenderInfo = this.renderViewImpl(parentBounds, siblingBounds, view) as any
+    const render: VRenderBatch<VRender> & CachedRenderInfo = this.renderViewImpl(parentBounds, siblingBounds, bounds, view) as any
+    if (!RendererImpl.isContainer(view)) {
+      this.markDirty(render.rect)
+    }
     render.parentBounds = parentBounds
     render.siblingBounds = siblingBounds
+    render.bounds = bounds
     render.parent = parent?.id ?? -1
+    render.hash = log?.hash ?? null
     this.cachedRenders.set(view.id, render)
     return render
   }
 
+  /**
+   * Path of component keys, view types and keys, and child indices from the root, e.g. `/RootComponent/box[2]/Counter/text`.
+   * Unlike view ids, this is the same across runs
+   */
+  private static renderPath (parentPath: string, parent: VView | null, node: VNode): string {
+    let path = parentPath
+    if (parent?.type === 'box' || parent?.type === 'fragment') {
+      path += `[${parent.children.indexOf(node)}]`
+    }
+    while (node.type === 'component') {
+      path += `/${node.key}`
+      node = node.node!
+    }
+    path += `/${node.type}`
+    if (node.key !== undefined) {
+      path += `:${node.key}`
+    }
+    return path
+  }
+
+  /**
+   * Stable hash (FNV-1a) of the render path and view structure (type, attributes, text, number of children; not ids).
+   * Logged with renders so logs from separate runs can be diffed to find which views stopped being cache hits
+   */
+  private static renderHash (path: string, view: VView): string {
+    const structure = JSON.stringify(view, (key, value) => {
+      if (key === 'id' || key === 'pixi') {
+        return undefined
+      } else if (key === 'children') {
+        return (value as VNode[]).length
+      } else if (typeof value === 'function') {
+        return 'fn'
+      } else {
+        return value
+      }
+    })
+    let hash = 0x811c9dc5
+    for (const char of `${path}|${structure}`) {
+      hash ^= char.codePointAt(0)!
+      hash = Math.imul(hash, 0x01000193) >>> 0
+    }
+    return hash.toString(16).padStart(8, '0')
+  }
+
+  private markDirty (rect: Rectangle | null): void {
+    if (rect !== null && this.dirtyRects !== null) {
+      this.dirtyRects.push(rect)
+    }
+  }
+
+  /**
+   * Whether the view only merges its children's renders, so re-rendering it doesn't change anything by itself:
+   * the children which changed or moved mark their own old and new rects
+   */
+  private static isContainer (view: VView): boolean {
+    return view.type === 'box' || view.type === 'fragment'
+  }
+
+  /** Removes rects inside of other rects, e.g. a re-rendered view's children */
+  private static simplifyDirtyRects (rects: Rectangle[]): Rectangle[] {
+    const contains = (outer: Rectangle, inner: Rectangle): boolean =>
+      inner.left >= outer.left && inner.top >= outer.top &&
+      inner.left + inner.width <= outer.left + outer.width && inner.top + inner.height <= outer.top + outer.height
+    return rects.filter((rect, i) => !rects.some((other, j) =>
+      j !== i && contains(other, rect) && (!contains(rect, other) || j < i)
+    ))
+  }
+
+  /**
+   * Whether the view's descendants have `vw` or `vh` measurements, so they must be re-rendered when the viewport resizes
+   * even if the view's own bounds didn't change. Custom bounds functions which read the viewport directly aren't detected
+   */
+  private static usesViewport (view: VView): boolean {
+    if (view.type !== 'box' && view.type !== 'fragment') {
+      return false
+    }
+    return (view.type === 'box' && Measurement.usesViewport(view.sublayout?.gap)) ||
+      view.children.some(child => {
+        const childView = VNode.view(child)
+        const spec = childView.bounds?.spec
+        return (spec !== undefined && [spec.x, spec.y, spec.width, spec.height].some(Measurement.usesViewport)) ||
+          RendererImpl.usesViewport(childView)
+      })
+  }
+
+  /**
+   * Whether the view's render is determined by its resolved bounds,
+   * as opposed to also using the parent or sibling bounds (e.g. inferring size or keeping stored bounds)
+   */
+  private static dependsOnlyOnBounds (view: VView, bounds: BoundingBox): boolean {
+    switch (view.type) {
+      case 'box':
+        return view.sublayout?.keep === undefined && typeof view.sublayout?.custom !== 'function'
+      case 'fragment':
+        // Children are laid out relative to the fragment's sibling
+        return false
+      case 'text':
+      case 'source':
+        return true
+      case 'color':
+      case 'border':
+      case 'pixi':
+        return bounds.width !== undefined && bounds.height !== undefined
+    }
+  }
+
   private getRootParentBounds (): ParentBounds {
+    const dimensions = this.getRootDimensions()
     return {
-      ...this.getRootDimensions(),
+      ...dimensions,
       columnSize: DEFAULT_COLUMN_SIZE,
-      sublayout: {}
+      sublayout: {},
+      viewport: { width: dimensions.boundingBox.width ?? 0, height: dimensions.boundingBox.height ?? 0 }
     }
   }
 
-  private renderViewImpl (parentBounds: ParentBounds, siblingBounds: Rectangle | null, view: VView): VRenderBatch<VRender> {
-    if (view.visible === false) {
-      return { rect: null }
+  /** `bounds` is null iff the view is invisible */
+  private renderViewImpl (parentBounds: ParentBounds, siblingBounds: Rectangle | null, bounds: BoundingBox | null, view: VView): VRenderBatch<VRender> {
+    if (bounds === null) {
+      return { layers: [], rect: null }
+    }
+    if (view.allowEmpty !== true && ((bounds.width !== undefined && bounds.width <= 0) || (bounds.height !== undefined && bounds.height <= 0))) {
+      Diagnostics.warn(`${view.type}${view.key !== undefined ? ` ${view.key}` : ''} has zero or negative size, set allowEmpty if this is intentional`)
+    }
+
+    const render = this.renderViewContent(parentBounds, siblingBounds, bounds, view)
+
+    // Apply transforms
+    if (view.offset !== undefined) {
+      const offset = { x: Math.round(view.offset.x), y: Math.round(view.offset.y) }
+      render.layers = render.layers.map(layer => ({ ...layer, render: this.translateRender(offset, parentBounds.columnSize, layer.render) }))
+      if (render.rect !== null) {
+        render.rect = Rectangle.translate(render.rect, offset)
+      }
+      if (render.cursor !== undefined) {
+        render.cursor = { ...render.cursor, x: render.cursor.x + offset.x, y: render.cursor.y + offset.y }
+      }
+      if (render.clickTargets !== undefined) {
+        render.clickTargets = render.clickTargets.map(target => ({ ...target, rect: Rectangle.translate(target.rect, offset) }))
+      }
+    }
+    if (view.dim !== undefined && view.dim > 0) {
+      const dim = Math.min(view.dim, 1)
+      render.layers = render.layers.map(layer => ({ ...layer, render: this.dimRender(dim, layer.render) }))
+    }
+
+    // Add click target
+    if ((view.onClick !== undefined || view.onWheel !== undefined || view.focus !== undefined) && render.rect !== null) {
+      render.clickTargets = [{ rect: render.rect, z: bounds.z, zIndices: [], onClick: view.onClick, onWheel: view.onWheel, focus: view.focus }, ...(render.clickTargets ?? [])]
+    }
+
+    return render
+  }
+
+  /** Renders and merges `children` laid out in `parentBounds`, the first after `siblingBounds` */
+  private renderChildren (parent: VView, parentBounds: ParentBounds, siblingBounds: Rectangle | null, children: readonly VNode[]): VRenderBatch<VRender> {
+    // Render children
+    const childRenders = []
+    let lastChild = null
+    for (const child of children) {
+      const childRender = this.renderNode(parent, parentBounds, lastChild?.rect ?? siblingBounds, child)
+      childRenders.push(childRender)
+      // Hidden and empty children don't reset the layout of the next sibling
+      if (childRender.rect !== null) {
+        lastChild = childRender
+      }
+    }
+
+    const minContrastRatio = getMinContrastRatio()
+    if (minContrastRatio !== null) {
+      RendererImpl.checkContrast(children, childRenders, minContrastRatio)
+    }
+
+    // Merge child renders
+    const mergedRender: VRenderBatch<VRender> = { layers: [], rect: null }
+    childRenders.forEach((child, index) => {
+      mergedRender.rect = Rectangle.union(mergedRender.rect, child.rect)
+      // The earliest child's cursor wins
+      if (child.cursor !== undefined && mergedRender.cursor === undefined) {
+        mergedRender.cursor = child.cursor
+      }
+      // Copy because child layers and click targets may be cached
+      const zIndex = VNode.view(children[index]).zIndex ?? 0
+      if (child.clickTargets !== undefined) {
+        mergedRender.clickTargets = [
+          ...(mergedRender.clickTargets ?? []),
+          ...child.clickTargets.map(target => ({ ...target, zIndices: [zIndex, ...target.zIndices] }))
+        ]
+      }
+      for (const layer of child.layers) {
+        mergedRender.layers.push({ ...layer, zIndices: [zIndex, ...layer.zIndices] })
+      }
+    })
+    // Stable, so earlier children are rendered above later ones with the same z
+    mergedRender.layers.sort(VRenderLayer.compare)
+
+    return mergedRender
+  }
+
+  /**
+   * Draws each box's rect, and labels each view with its bounds spec and resulting rect.
+   * Cyan: explicit size, yellow: size inferred from the content, red: overflows its parent
+   */
+  setLayoutDebug (enabled: boolean): void {
+    this.layoutDebug = enabled
+    this.needsRerender = true
+    this.dirtyRects = null
+  }
+
+  /** Returns a copy of `render` (which may be cached) with the layout debug overlay on top */
+  private addLayoutDebugOverlay (render: VRenderBatch<VRender>): VRenderBatch<VRender> {
+    // Bottommost first, reversed at the end
+    const overlayLayers: Array<VRenderLayer<VRender>> = []
+    const addOverlay = (z: number, layer: VRender): void => {
+      overlayLayers.push({ render: layer, z, zIndices: [LAYOUT_DEBUG_Z_INDEX] })
+    }
+    let z = 0
+    const visit = (node: VNode): void => {
+      const view = VNode.view(node)
+      const cachedRender = this.cachedRenders.get(view.id)
+      if (cachedRender === undefined || cachedRender.bounds === null) {
+        return
+      }
+      const { parentBounds, bounds, rect } = cachedRender
+      if (rect !== null) {
+        const parentBox = parentBounds.boundingBox
+        const overflows = (parentBox.width !== undefined && rect.left + rect.width > parentBox.x + parentBox.width) ||
+          (parentBox.height !== undefined && rect.top + rect.height > parentBox.y + parentBox.height)
+        const color = Color(overflows ? 'red' : bounds.width === undefined || bounds.height === undefined ? 'yellow' : 'cyan')
+        if (view.type === 'box' && rect.width >= 2 && rect.height >= 2) {
+          addOverlay(z, this.renderBorder(rect, parentBounds.columnSize, color, 'single', view))
+          z += Bounds.DELTA_Z
+        }
+        if (view.type === 'box' || overflows) {
+          const label = `${view.type}${view.key !== undefined ? `#${view.key}` : ''} ${RendererImpl.describeBoundsSpec(view.bounds)}→ ${rect.left},${rect.top} ${rect.width}x${rect.height}`
+          const labelBounds: BoundingBox = { x: rect.left + 1, y: rect.top, z, anchorX: 0, anchorY: 0, width: Math.max(1, rect.width - 2), height: 1 }
+          addOverlay(z, this.renderText(labelBounds, parentBounds.columnSize, 'clip', color, null, null, label, view))
+          z += Bounds.DELTA_Z
+        }
+      }
+      if (view.type === 'box' || view.type === 'fragment') {
+        view.children.forEach(visit)
+      }
+    }
+    visit(this.root!)
+    return { ...render, layers: [...overlayLayers.reverse(), ...render.layers] }
+  }
+
+  /** e.g. `x=50% width=prev + 2 `, or '' if the bounds have no spec */
+  private static describeBoundsSpec (bounds: Bounds | undefined): string {
+    const spec = bounds?.spec
+    if (spec === undefined) {
+      return ''
+    }
+    return Object.entries(spec)
+      .filter(([, value]) => value !== undefined)
+      .map(([key, value]) => `${key}=${typeof value === 'object' ? JSON.stringify(value) : String(value)} `)
+      .join('')
+  }
+
+  /**
+   * Warns if a text child has low contrast against its background: its own `style.background` if it has one,
+   * otherwise the topmost overlapping `color` sibling rendered below it, in the order the layers are merged (by `zIndex`, then `z`, then document order)
+   */
+  private static checkContrast (children: readonly VNode[], childRenders: ReadonlyArray<VRenderBatch<unknown>>, minContrastRatio: number): void {
+    // Like the child's topmost layer once merged, see renderChildren
+    const layerOrder = (index: number): Pick<VRenderLayer<unknown>, 'z' | 'zIndices'> | null => {
+      const layer = childRenders[index].layers[0]
+      return layer === undefined ? null : { z: layer.z, zIndices: [VNode.view(children[index]).zIndex ?? 0, ...layer.zIndices] }
+    }
+    // Negative if the child at `lhs` is rendered above the child at `rhs`. Sorting is stable, so earlier children are above
+    const compareChildren = (lhs: number, rhs: number): number => {
+      const difference = VRenderLayer.compare(layerOrder(lhs)!, layerOrder(rhs)!)
+      return difference !== 0 ? difference : lhs - rhs
     }
 
-    const bounds = (view.bounds ?? Bounds.DEFAULT)(parentBounds, siblingBounds)
+    children.forEach((child, index) => {
+      const text = VNode.view(child)
+      const textRect = childRenders[index].rect
+      if (text.type !== 'text' || text.color === null || textRect === null || layerOrder(index) === null) {
+        return
+      }
+      let background: ColorSpec | null = text.style?.background ?? null
+      if (background === null) {
+        // Only the topmost background is visible
+        let topmost: number | null = null
+        for (let i = 0; i < children.length; i++) {
+          const sibling = VNode.view(children[i])
+          const overlap = Rectangle.intersection(textRect, childRenders[i].rect)
+          if (sibling.type === 'color' && overlap !== null && overlap.width > 0 && overlap.height > 0 && layerOrder(i) !== null &&
+            compareChildren(index, i) < 0 && (topmost === null || compareChildren(i, topmost) < 0)) {
+            topmost = i
+            background = sibling.color
+          }
+        }
+      }
+      if (background === null) {
+        return
+      }
+      const ratio = Color.contrastRatio(text.color, background)
+      if (ratio < minContrastRatio) {
+        Diagnostics.warn(`low contrast (${ratio.toFixed(2)}, minimum is ${minContrastRatio}) between text and its background: ${JSON.stringify(text.text.slice(0, 32))}`)
+      }
+    })
+  }
 
+  private renderViewContent (parentBounds: ParentBounds, siblingBounds: Rectangle | null, bounds: BoundingBox, view: VView): VRenderBatch<VRender> {
     switch (view.type) {
       case 'box': {
         const bounds2: ParentBounds = {
           boundingBox: bounds,
           sublayout: DelayedSubLayout.resolve(view.sublayout ?? {}, bounds, parentBounds, siblingBounds),
-          columnSize: parentBounds.columnSize
+          columnSize: parentBounds.columnSize,
+          viewport: parentBounds.viewport
         }
 
-        // Render children
-        const children = []
-        let lastChild = null
-        for (const child of view.children) {
-          const childRender = this.renderNode(view, bounds2, lastChild?.rect ?? null, child)
-          children.push(childRender)
-          lastChild = childRender
-        }
-
-        // Reverse so renders earlier are rendered above
-        children.reverse()
-
-        // Merge child renders
-        const mergedRender: VRenderBatch<VRender> = { rect: null }
-        for (const child of children) {
-          mergedRender.rect = Rectangle.union(mergedRender.rect, child.rect)
-          for (const [zString, render] of Object.entries(child)) {
-            let zPosition = Number(zString)
-            if (!isNaN(zPosition)) {
-              while (zPosition in mergedRender) {
-                zPosition += Bounds.DELTA_Z
-              }
-              mergedRender[zPosition] = render
-            }
-          }
-        }
+        const mergedRender = this.renderChildren(view, bounds2, null, view.children)
 
         // Clip if necessary
         if (view.clip === true) {
@@ -243,11 +1055,15 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
           } else {
             mergedRender.rect = Rectangle.intersection(mergedRender.rect, clipRect)
           }
-          for (const zString in mergedRender) {
-            const zPosition = Number(zString)
-            if (!isNaN(zPosition)) {
-              mergedRender[zPosition] = this.clipRender(clipRect, parentBounds.columnSize, mergedRender[zPosition])
-            }
+          mergedRender.layers = mergedRender.layers.map(layer => ({ ...layer, render: this.clipRender(clipRect, parentBounds.columnSize, layer.render) }))
+          if (mergedRender.cursor !== undefined && !Rectangle.contains(clipRect, mergedRender.cursor)) {
+            delete mergedRender.cursor
+          }
+          if (mergedRender.clickTargets !== undefined) {
+            mergedRender.clickTargets = mergedRender.clickTargets.flatMap(target => {
+              const rect = Rectangle.intersection(target.rect, clipRect)
+              return rect === null ? [] : [{ ...target, rect }]
+            })
           }
         } else if (view.extend === true) {
           if (mergedRender.rect !== null && bounds.width !== undefined && mergedRender.rect.width < bounds.width) {
@@ -260,16 +1076,26 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
 
         return mergedRender
       }
+      case 'fragment':
+        return this.renderChildren(view, parentBounds, siblingBounds, view.children)
       case 'text': {
         const lines = view.text.split('\n')
         const rect = BoundingBox.toRectangle(bounds, {
-          width: Math.max(0, ...lines.map(Strings.width)),
+          width: Math.max(0, ...lines.map(Graphemes.lineWidth)),
           height: lines.length
         })
-        return {
+        const render: VRenderBatch<VRender> = {
           rect,
-          [bounds.z]: this.renderText(bounds, parentBounds.columnSize, view.wrapMode, view.color, view.text, view)
+          layers: [VRenderLayer.of(bounds.z, this.renderText(bounds, parentBounds.columnSize, view.wrapMode, view.color, view.style ?? null, view.link ?? null, view.text, view))]
         }
+        if (view.cursor !== undefined) {
+          render.cursor = {
+            x: rect.left + view.cursor.x,
+            y: rect.top + view.cursor.y,
+            shape: view.cursor.shape ?? 'bar'
+          }
+        }
+        return render
       }
       case 'color': {
         const inferredBounds = {
@@ -283,7 +1109,7 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
         const rect = BoundingBox.toRectangle(inferredBounds as BoundingBox & Size)
         return {
           rect,
-          [bounds.z]: this.renderSolidColor(rect, parentBounds.columnSize, view.color, view)
+          layers: [VRenderLayer.of(bounds.z, this.renderSolidColor(rect, parentBounds.columnSize, view.color, view))]
         }
       }
       case 'border': {
@@ -298,7 +1124,7 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
         const rect = BoundingBox.toRectangle(inferredBounds as BoundingBox & Size)
         return {
           rect,
-          [bounds.z]: this.renderBorder(rect, parentBounds.columnSize, view.color, view.style, view)
+          layers: [VRenderLayer.of(bounds.z, this.renderBorder(rect, parentBounds.columnSize, view.color, view.style, view))]
         }
       }
       case 'source': {
@@ -312,7 +1138,7 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
             const rect = BoundingBox.toRectangle(bounds, size)
             return {
               rect,
-              [bounds.z]: render
+              layers: [VRenderLayer.of(bounds.z, render)]
             }
           }
           case 'svg': {
@@ -320,7 +1146,7 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
             const rect = BoundingBox.toRectangle(bounds, size)
             return {
               rect,
-              [bounds.z]: render
+              layers: [VRenderLayer.of(bounds.z, render)]
             }
           }
           case undefined:
@@ -339,7 +1165,7 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
         const rect = size !== null ? BoundingBox.toRectangle(bounds, size) : null
         return {
           rect,
-          [bounds.z]: render
+          layers: [VRenderLayer.of(bounds.z, render)]
         }
       }
     }
@@ -350,10 +1176,188 @@ export abstract class RendererImpl<VRender, AssetCacher extends CoreAssetCacher>
       this.stop()
     }
 
-    VComponent.destroy(this.root!)
-    this.root = null
+    try {
+      VComponent.destroy(this.root!)
+    } finally {
+      this.root = null
+
+      for (const destructor of [...this.inputSourceDestructors]) {
+        destructor()
+      }
+      this.releaseLeakedHandlers()
+    }
+
+    if (this.coalesceTimer !== null) {
+      clearTimeout(this.coalesceTimer)
+      this.coalesceTimer = null
+    }
+    this.pendingCoalescedInput = []
+
+    const waiters = this.rerenderWaiters
+    this.rerenderWaiters = []
+    for (const waiter of waiters) {
+      waiter.reject(new Error('Renderer was disposed'))
+    }
+  }
+
+  /**
+   * Reports handlers which components registered but didn't remove when they were destroyed
+   * (usually because an effect didn't return the function which removes it), and removes every handler,
+   * so they don't keep what they reference alive
+   */
+  private releaseLeakedHandlers (): void {
+    this.leakedHandlers = this.componentHandlers.size
+    if (this.componentHandlers.size > 0 && isDebugMode()) {
+      const paths = [...new Set(this.componentHandlers.values())]
+      Diagnostics.warn(`${this.componentHandlers.size} handler(s) were still registered after their components were destroyed, registered by: ${paths.join(', ')}`)
+    }
+    this.componentHandlers.clear()
+    this.inputHandlers.clear()
+    this.inputCaptures.length = 0
+    this.pasteHandlers.clear()
+    this.mouseHandlers.clear()
+    this.exitBlockers.clear()
+  }
+
+  /**
+   * Stops the renderer, runs all destructors, restores the platform (e.g. terminal modes), and returns what was left.
+   * If `flush` is set and there are unrendered changes, renders them first.
+   */
+  shutdown (flush: boolean = false): ShutdownSummary<VRenderBatch<VRender>> {
+    if (flush && this.needsRerender && this.isVisible) {
+      this.forceRerender()
+    }
+    const summary = {
+      hadPendingRerender: this.needsRerender,
+      generation: this.generation,
+      lastFrame: this.lastRender
+    }
+    this.dispose()
+    return { ...summary, leakedHandlers: this.leakedHandlers }
   }
 
+  /** {@link shutdown}, then resolves once everything written has been flushed */
+  async shutdownAsync (flush: boolean = true): Promise<ShutdownSummary<VRenderBatch<VRender>>> {
+    const summary = this.shutdown(flush)
+    await this.flushOutput()
+    return summary
+  }
+
+  getCapabilities (): Capabilities {
+    return { ...this.getPlatformCapabilities(), fps: this.defaultFps }
+  }
+
+  protected abstract getPlatformCapabilities (): Omit<Capabilities, 'fps'>
+
+  /**
+   * Adds a function which is called by {@link requestShutdown} and can prevent it by returning false,
+   * e.g. after asking the user to confirm discarding unsaved changes. Returns a function which removes it
+   */
+  addExitBlocker (blocker: () => boolean | Promise<boolean>): () => void {
+    this.exitBlockers.add(blocker)
+    const untrack = this.trackComponentHandler(blocker)
+    return () => {
+      this.exitBlockers.delete(blocker)
+      untrack()
+    }
+  }
+
+  /**
+   * Asks every exit blocker (one at a time, since they may show UI) whether to shut down.
+   * If all allow it, shuts down like {@link shutdownAsync} and returns the summary, otherwise returns null and keeps running
+   */
+  async requestShutdown (flush: boolean = true): Promise<ShutdownSummary<VRenderBatch<VRender>> | null> {
+    for (const blocker of [...this.exitBlockers]) {
+      if (!await blocker()) {
+        return null
+      }
+    }
+    return await this.shutdownAsync(flush)
+  }
+
+  /**
+   * Finds views in the current tree by selector (see {@link Query.parse}), e.g. `renderer.query("text:contains('Save')")`,
+   * or by predicate. Rects are from the last render, so call after rendering (e.g. {@link waitForRerender}) to get up-to-date positions.
+   * To click a match, `renderer.sendMouse({ type: 'press', button: 'left', ...Query.center(match)! })`
+   */
+  query (selector: string | QueryPredicate): QueryMatch[] {
+    if (this.root === null || this.root.node === null) {
+      return []
+    }
+    const matcher = typeof selector === 'string' ? Query.parse(selector) : Query.fromPredicate(selector)
+    return Query.run(this.root, matcher, view => this.cachedRenders.get(view.id)?.rect ?? null)
+  }
+
+  /**
+   * Roles, labels, values, focus and rects of the views in the current tree, for assistive tooling and tests.
+   * See {@link AccessibilityNode} for the JSON schema, and `listenAccessibility` to serve it over a socket
+   */
+  accessibilityTree (): AccessibilityTree {
+    if (this.root === null || this.root.node === null) {
+      return { version: Accessibility.SCHEMA_VERSION, root: null }
+    }
+    return Accessibility.tree(this.root, view => this.cachedRenders.get(view.id)?.rect ?? null)
+  }
+
+  /**
+   * The text of the last frame by row, then column: one grapheme per cell, `''` in the second cell of wide graphemes,
+   * and `' '` where there's no text. e.g. to search or copy what's on screen
+   */
+  getScreenCells (): string[][] {
+    if (this.lastRender === null) {
+      return []
+    }
+    return this.renderToCells(this.lastRender)
+  }
+  /** Size of the root (the terminal or canvas) in columns and rows, which `vw` and `vh` measurements are percentages of */
+  getViewportSize (): Size {
+    return this.getRootParentBounds().viewport
+  }
+
+
+  /**
+   * Places each text view's lines at its rect, without wrapping, earlier views above later ones.
+   * Renderers which draw a grid of cells override this to return exactly what was drawn
+   */
+  protected renderToCells (render: VRenderBatch<VRender>): string[][] {
+    const cells: string[][] = []
+    const visit = (node: VNode): void => {
+      const view = VNode.view(node)
+      const rect = this.cachedRenders.get(view.id)?.rect ?? null
+      if (view.type === 'text' && rect !== null) {
+        view.text.split('\n').forEach((line, lineIndex) => {
+          const y = Math.round(rect.top) + lineIndex
+          let x = Math.round(rect.left)
+          for (const grapheme of Graphemes.split(line)) {
+            const width = Graphemes.width(grapheme)
+            if (y >= 0 && x >= 0 && width > 0) {
+              cells[y] = cells[y] ?? []
+              const row = cells[y]
+              if (row[x] === undefined) {
+                row[x] = grapheme
+                if (width === 2) {
+                  row[x + 1] = ''
+                }
+              }
+            }
+            x += width
+          }
+        })
+      } else if (view.type === 'box' || view.type === 'fragment') {
+        view.children.forEach(visit)
+      }
+    }
+    if (this.root !== null && this.root.node !== null) {
+      visit(this.root)
+    }
+    const width = Math.max(0, ...cells.map(row => row.length), Math.ceil(render.rect === null ? 0 : render.rect.left + render.rect.width))
+    const height = Math.max(cells.length, Math.ceil(render.rect === null ? 0 : render.rect.top + render.rect.height))
+    return Array.from({ length: height }, (_, y) => Array.from({ length: width }, (_, x) => cells[y]?.[x] ?? ' '))
+  }
+
+  /** Resolves once all output written so far has been flushed */
+  protected async flushOutput (): Promise<void> {}
+
   private static logRender (...args: any[]): void {
     if (doLogRender()) {
       console.log(...args)
@@ -0,0 +1,183 @@
+import { BorderStyle, BoundingBox, Color, Rectangle, Size, Style, VView } from 'core/view'
+import { Capabilities, CoreRenderOptions, DEFAULT_COLUMN_SIZE } from 'core/renderer'
+import { VComponent } from 'core/component'
+import { Graphemes } from 'core/graphemes'
+import { CoreAssetCacher, CursorPosition, RendererImpl, VRenderBatch } from 'renderer/common'
+import type { DisplayObject } from 'pixi.js'
+
+/** Something the mock renderer drew. Rects are in columns and rows */
+export type MockDraw = {
+  rect: Rectangle
+  /** Accumulated `dim` of the view and its ancestors */
+  dim: number
+  viewId: number
+} & (
+  { type: 'text', text: string, color: Color | null, style: Style | null, link: string | null } |
+  { type: 'color', color: Color } |
+  { type: 'border', color: Color | null, style: BorderStyle } |
+  { type: 'image', src: string } |
+  { type: 'pixi' }
+)
+
+export type MockRender = MockDraw[]
+
+/** One call to `writeRender` */
+export interface MockFrame {
+  /** Everything drawn, topmost first */
+  draws: MockDraw[]
+  rect: Rectangle | null
+  cursor: CursorPosition | null
+  /** Rects which changed since the previous frame, or null if this frame was written entirely */
+  dirtyRects: Rectangle[] | null
+}
+
+export interface MockRenderOptions extends CoreRenderOptions {
+  /** Root width and height in columns and rows. Default is 80x24 */
+  size?: Size
+  columnSize?: Size
+  /** Default is a terminal with 256 colors and mouse support */
+  capabilities?: Partial<Omit<Capabilities, 'fps'>>
+  /** Sizes of images by `src`. Images not in here are the size of their bounds, or 1x1 */
+  imageSizes?: Record<string, Size>
+}
+
+const DEFAULT_MOCK_CAPABILITIES: Omit<Capabilities, 'fps'> = {
+  images: 'approximate',
+  colors: 256,
+  mouse: true,
+  cursor: true,
+  links: false,
+  clipboard: true
+}
+
+class AssetCacher extends CoreAssetCacher {}
+
+/**
+ * A renderer which doesn't output anywhere: it records what it would draw in {@link frames},
+ * so logic which depends on a renderer can be tested without a terminal or browser.
+ * The root size and capabilities can be changed at any time
+ */
+export class MockRendererImpl extends RendererImpl<MockRender, AssetCacher> {
+  /** Every frame written, oldest first */
+  readonly frames: MockFrame[] = []
+  /** Every string written to the clipboard, oldest first */
+  readonly clipboard: string[] = []
+  /** Number of times the output was cleared */
+  clears: number = 0
+
+  private size: Size
+  private readonly columnSize: Size
+  private capabilities: Omit<Capabilities, 'fps'>
+  private readonly imageSizes: Record<string, Size>
+
+  constructor (root: () => VComponent, opts: MockRenderOptions = {}) {
+    super(new AssetCacher(), opts)
+    this.size = opts.size ?? { width: 80, height: 24 }
+    this.columnSize = opts.columnSize ?? DEFAULT_COLUMN_SIZE
+    this.capabilities = { ...DEFAULT_MOCK_CAPABILITIES, ...opts.capabilities }
+    this.imageSizes = opts.imageSizes ?? {}
+    this.finishInit(root)
+  }
+
+  /** The last frame written, or null if none */
+  get lastFrame (): MockFrame | null {
+    return this.frames[this.frames.length - 1] ?? null
+  }
+
+  /** Text drawn in the last frame, topmost first */
+  lastTexts (): string[] {
+    return (this.lastFrame?.draws ?? []).flatMap(draw => draw.type === 'text' ? [draw.text] : [])
+  }
+
+  /** Changes the root size, as if the terminal or window was resized */
+  resize (size: Size): void {
+    this.size = size
+    this.invalidateRoot()
+  }
+
+  setCapabilities (capabilities: Partial<Omit<Capabilities, 'fps'>>): void {
+    this.capabilities = { ...this.capabilities, ...capabilities }
+  }
+
+  override writeClipboard (text: string): void {
+    this.clipboard.push(text)
+  }
+
+  protected override getPlatformCapabilities (): Omit<Capabilities, 'fps'> {
+    return this.capabilities
+  }
+
+  protected override clear (): void {
+    this.clears++
+  }
+
+  protected override writeRender (render: VRenderBatch<MockRender>): void {
+    this.frames.push({
+      draws: render.layers.flatMap(layer => layer.render),
+      rect: render.rect,
+      cursor: render.cursor ?? null,
+      dirtyRects: null
+    })
+  }
+
+  protected override writeRenderDiff (render: VRenderBatch<MockRender>, dirtyRects: Rectangle[]): void {
+    this.frames.push({
+      draws: render.layers.flatMap(layer => layer.render),
+      rect: render.rect,
+      cursor: render.cursor ?? null,
+      dirtyRects
+    })
+  }
+
+  protected override getRootDimensions (): { boundingBox: BoundingBox, columnSize?: Size } {
+    return {
+      boundingBox: { x: 0, y: 0, z: 0, anchorX: 0, anchorY: 0, width: this.size.width, height: this.size.height },
+      columnSize: this.columnSize
+    }
+  }
+
+  protected override translateRender (offset: { x: number, y: number }, columnSize: Size, render: MockRender): MockRender {
+    // Copy because render may be cached
+    return render.map(draw => ({ ...draw, rect: Rectangle.translate(draw.rect, offset) }))
+  }
+
+  protected override dimRender (dim: number, render: MockRender): MockRender {
+    return render.map(draw => ({ ...draw, dim: 1 - (1 - draw.dim) * (1 - dim) }))
+  }
+
+  protected override clipRender (clipRect: Rectangle, columnSize: Size, render: MockRender): MockRender {
+    return render.flatMap(draw => {
+      const rect = Rectangle.intersection(draw.rect, clipRect)
+      return rect === null ? [] : [{ ...draw, rect }]
+    })
+  }
+
+  protected override renderText (bounds: BoundingBox, columnSize: Size, wrapMode: 'word' | 'char' | 'clip' | undefined, color: Color | null, style: Style | null, link: string | null, text: string, node: VView): MockRender {
+    const lines = text.split('\n')
+    const rect = BoundingBox.toRectangle(bounds, { width: Math.max(0, ...lines.map(Graphemes.lineWidth)), height: lines.length })
+    return [{ type: 'text', rect, dim: 0, viewId: node.id, text, color, style, link }]
+  }
+
+  protected override renderSolidColor (rect: Rectangle, columnSize: Size, color: Color, node: VView): MockRender {
+    return [{ type: 'color', rect, dim: 0, viewId: node.id, color }]
+  }
+
+  protected override renderBorder (rect: Rectangle, columnSize: Size, color: Color | null, style: BorderStyle, node: VView): MockRender {
+    return [{ type: 'border', rect, dim: 0, viewId: node.id, color, style }]
+  }
+
+  protected override renderImage (bounds: BoundingBox, columnSize: Size, src: string, node: VView): { render: MockRender, size: Size } {
+    const size = this.imageSizes[src] ?? { width: bounds.width ?? 1, height: bounds.height ?? 1 }
+    return { render: [{ type: 'image', rect: BoundingBox.toRectangle(bounds, size), dim: 0, viewId: node.id, src }], size }
+  }
+
+  protected override renderVectorImage (bounds: BoundingBox, columnSize: Size, src: string, node: VView): { render: MockRender, size: Size } {
+    return this.renderImage(bounds, columnSize, src, node)
+  }
+
+  protected override renderPixi (bounds: BoundingBox, columnSize: Size, pixi: DisplayObject | 'terminal', getSize: ((pixi: DisplayObject, bounds: BoundingBox, columnSize: Size) => Size) | undefined, node: VView): { render: MockRender, size: Size | null } {
+    const size = pixi !== 'terminal' && getSize !== undefined ? getSize(pixi, bounds, columnSize) : null
+    const rect = BoundingBox.toRectangle(bounds, size ?? { width: bounds.width ?? 0, height: bounds.height ?? 0 })
+    return { render: [{ type: 'pixi', rect, dim: 0, viewId: node.id }], size }
+  }
+}
@@ -1,9 +1,10 @@
-import { BorderStyle, BoundingBox, Color, Rectangle, Size } from 'core/view'
-import { CoreRenderOptions, DEFAULT_COLUMN_SIZE } from 'core/renderer'
+import { BorderStyle, BoundingBox, Color, Rectangle, Size, Style } from 'core/view'
+import { Capabilities, CoreRenderOptions, DEFAULT_COLUMN_SIZE } from 'core/renderer'
 import { CoreAssetCacher, RendererImpl, VRenderBatch } from 'renderer/common'
 import { Key, Strings } from '@raycenity/misc-ts'
 import type { Application, DisplayObject, IApplicationOptions, Sprite, Texture } from 'pixi.js'
 import { VComponent } from 'core/component'
+import { Diagnostics } from 'core/diagnostics'
 
 declare global {
   const PIXI: typeof import('pixi.js')
@@ -49,6 +50,13 @@ export class BrowserRendererImpl extends RendererImpl<VRender, AssetCacher> {
     }
     this.em = opts.em ?? null
 
+    document.body.addEventListener('keypress', this.onKeypress)
+    document.addEventListener('paste', this.onPaste)
+    this.canvas.view.addEventListener('pointerdown', this.onPointerDown)
+    this.canvas.view.addEventListener('pointerup', this.onPointerUp)
+    this.canvas.view.addEventListener('pointermove', this.onPointerMove)
+    this.canvas.view.addEventListener('wheel', this.onWheel)
+
     this.finishInit(root)
   }
 
@@ -57,10 +65,8 @@ export class BrowserRendererImpl extends RendererImpl<VRender, AssetCacher> {
   }
 
   protected override writeRender (render: VRenderBatch<VRender>): void {
-    const collapsed = Object.entries(render)
-      .filter(([key]) => !isNaN(parseFloat(key)))
-      .sort(([lhs], [rhs]) => Number(lhs) - Number(rhs))
-      .map(([, value]) => value)
+    // Children added later are drawn above
+    const collapsed = render.layers.map(layer => layer.render).reverse()
     this.canvas.stage.addChild(...collapsed)
   }
 
@@ -68,12 +74,7 @@ export class BrowserRendererImpl extends RendererImpl<VRender, AssetCacher> {
     boundingBox: BoundingBox
     columnSize?: Size
   } {
-    const columnSize = this.em !== null
-      ? {
-          width: this.em / 2,
-          height: this.em
-        }
-      : DEFAULT_COLUMN_SIZE
+    const columnSize = this.getColumnSize()
     return {
       boundingBox: {
         x: 0,
@@ -88,6 +89,33 @@ export class BrowserRendererImpl extends RendererImpl<VRender, AssetCacher> {
     }
   }
 
+  private getColumnSize (): Size {
+    return this.em !== null
+      ? {
+          width: this.em / 2,
+          height: this.em
+        }
+      : DEFAULT_COLUMN_SIZE
+  }
+
+  // We wrap in containers because render may be a cached child render, and transforming it directly is not idempotent
+
+  protected override translateRender (offset: { x: number, y: number }, columnSize: Size, render: VRender): VRender {
+    const container = new PIXI.Container()
+    container.addChild(render)
+    container.position.set(offset.x * columnSize.width, offset.y * columnSize.height)
+    return container
+  }
+
+  protected override dimRender (dim: number, render: VRender): VRender {
+    const container = new PIXI.Container()
+    container.addChild(render)
+    const filter = new PIXI.filters.ColorMatrixFilter()
+    filter.brightness(1 - dim, false)
+    container.filters = [filter]
+    return container
+  }
+
   protected override clipRender (clipRect: Rectangle, columnSize: Size, render: VRender): VRender {
     const mask = new PIXI.Graphics()
     mask.beginFill(0)
@@ -102,7 +130,7 @@ export class BrowserRendererImpl extends RendererImpl<VRender, AssetCacher> {
     return render
   }
 
-  protected override renderText (bounds: BoundingBox, columnSize: Size, wrapMode: 'word' | 'char' | 'clip' | undefined, color: Color | null, text: string): VRender {
+  protected override renderText (bounds: BoundingBox, columnSize: Size, wrapMode: 'word' | 'char' | 'clip' | undefined, color: Color | null, style: Style | null, link: string | null, text: string): VRender {
     if (bounds.width !== undefined) {
       if (wrapMode === 'clip') {
         // Remove clipped characters
@@ -110,7 +138,7 @@ export class BrowserRendererImpl extends RendererImpl<VRender, AssetCacher> {
       } else if (wrapMode === undefined) {
         // Warn if characters go past end
         if (text.split('\n').some(line => Strings.width(line) > bounds.width!)) {
-          console.warn(`wrap is undefined but text goes path width (text = ${text})`)
+          Diagnostics.warn(`wrap is undefined but text goes path width (text = ${text})`)
         }
       }
     }
@@ -119,6 +147,8 @@ export class BrowserRendererImpl extends RendererImpl<VRender, AssetCacher> {
       fontFamily: 'monospace',
       fontSize: this.em ?? columnSize.height,
       fill: color === null ? 0x000000 : color2Number(color),
+      fontWeight: style?.bold === true ? 'bold' : 'normal',
+      fontStyle: style?.italic === true ? 'italic' : 'normal',
       align: 'left',
       wordWrap: wrapMode === 'word',
       wordWrapWidth: wrapMode === 'word' ? bounds.width : undefined,
@@ -126,13 +156,34 @@ export class BrowserRendererImpl extends RendererImpl<VRender, AssetCacher> {
     })
 
     transformSpriteRender(render, bounds, columnSize)
+    // TODO: underline and strikethrough aren't supported by PIXI.Text
+    if (style?.dim === true) {
+      render.alpha = 0.5
+    }
+
+    if (link !== null) {
+      render.interactive = true
+      render.buttonMode = true
+      render.on('pointertap', () => {
+        window.open(link, '_blank')
+      })
+    }
+
+    if (style?.background !== undefined && style.background !== null) {
+      const background = new PIXI.Graphics()
+      background.beginFill(color2Number(Color(style.background)), Color.toRGB(Color(style.background)).alpha ?? 1)
+      background.drawRect(render.x - render.anchor.x * render.width, render.y - render.anchor.y * render.height, render.width, render.height)
+      const container = new PIXI.Container()
+      container.addChild(background, render)
+      return container
+    }
 
     return render
   }
 
   protected override renderSolidColor (rect: Rectangle, columnSize: Size, color: Color): VRender {
     const pixiColor = new PIXI.Graphics()
-    pixiColor.beginFill(color2Number(color))
+    pixiColor.beginFill(color2Number(color), Color.toRGB(color).alpha ?? 1)
     pixiColor.drawRect(
       rect.left * columnSize.width,
       rect.top * columnSize.height,
@@ -202,7 +253,7 @@ export class BrowserRendererImpl extends RendererImpl<VRender, AssetCacher> {
         break
       case 'dashed':
       case 'ascii-dashed':
-        console.warn('TODO: dashed border style not supported by Pixi renderer')
+        Diagnostics.warn('TODO: dashed border style not supported by Pixi renderer')
         pixiColor.drawRect(
           rect.left * columnSize.width,
           rect.top * columnSize.height,
@@ -243,16 +294,61 @@ export class BrowserRendererImpl extends RendererImpl<VRender, AssetCacher> {
     }
   }
 
-  override useInput (handler: (key: Key) => void): () => void {
-    function listener (key: KeyboardEvent): void {
-      handler(Key.fromKeyboardEvent(key))
+  private readonly onKeypress = (key: KeyboardEvent): void => {
+    this.sendInput(Key.fromKeyboardEvent(key), 'browser')
+  }
+
+  private readonly onPointerDown = (event: PointerEvent): void => {
+    this.sendMouse({ type: 'press', ...this.getMousePosition(event) })
+  }
+
+  private readonly onPointerUp = (event: PointerEvent): void => {
+    this.sendMouse({ type: 'release', ...this.getMousePosition(event) })
+  }
+
+  private readonly onPointerMove = (event: PointerEvent): void => {
+    // event.button is -1 for moves, so get the held button from event.buttons
+    const button = (event.buttons & 1) !== 0 ? 'left' : (event.buttons & 4) !== 0 ? 'middle' : (event.buttons & 2) !== 0 ? 'right' : null
+    this.sendMouse({ type: 'move', ...this.getMousePosition(event), button })
+  }
+
+  private readonly onWheel = (event: WheelEvent): void => {
+    this.sendMouse({ type: event.deltaY < 0 ? 'wheel-up' : 'wheel-down', ...this.getMousePosition(event), button: null })
+  }
+
+  private getMousePosition (event: MouseEvent): { button: 'left' | 'middle' | 'right' | null, x: number, y: number } {
+    const columnSize = this.getColumnSize()
+    return {
+      button: (['left', 'middle', 'right'] as const)[event.button] ?? null,
+      x: event.offsetX / columnSize.width,
+      y: event.offsetY / columnSize.height
+    }
+  }
+
+  private readonly onPaste = (event: ClipboardEvent): void => {
+    const text = event.clipboardData?.getData('text')
+    if (text !== undefined && text !== '') {
+      this.sendPaste(text)
     }
-    document.body.addEventListener('keypress', listener)
-    return () => {
-      document.body.removeEventListener('keypress', listener)
+  }
+
+  protected override getPlatformCapabilities (): Omit<Capabilities, 'fps'> {
+    return {
+      images: 'native',
+      colors: 'truecolor',
+      mouse: true,
+      cursor: false,
+      links: true,
+      clipboard: navigator.clipboard !== undefined
     }
   }
 
+  override writeClipboard (text: string): void {
+    navigator.clipboard.writeText(text).catch(error => {
+      console.error('Failed to write to clipboard', error)
+    })
+  }
+
   override start (fps?: number): void {
     super.start(fps)
     this.canvas.start()
@@ -265,6 +361,12 @@ export class BrowserRendererImpl extends RendererImpl<VRender, AssetCacher> {
 
   override dispose (): void {
     super.dispose()
+    document.body.removeEventListener('keypress', this.onKeypress)
+    document.removeEventListener('paste', this.onPaste)
+    this.canvas.view.removeEventListener('pointerdown', this.onPointerDown)
+    this.canvas.view.removeEventListener('pointerup', this.onPointerUp)
+    this.canvas.view.removeEventListener('pointermove', this.onPointerMove)
+    this.canvas.view.removeEventListener('wheel', this.onWheel)
     this.canvas.destroy()
   }
 }